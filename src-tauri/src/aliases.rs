@@ -0,0 +1,182 @@
+use crate::keystore::{AccountRole, KeyStore, StoredAccount};
+use crate::settings::ChainSettings;
+use crate::{running_chain, AppState};
+use concordium_rust_sdk::smart_contracts::common::AccountAddress;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLock};
+use tauri::State;
+
+/// Human-readable names for the accounts of a chain, kept in the chain settings.
+///
+/// The accounts in the key store are named when the chain is first loaded: `baker-<n>` for bakers, `foundation` for the
+/// foundation account and `account-<n>` for the other genesis accounts. Users can rename them and name any other
+/// address, and commands accept an alias wherever they take an account address.
+#[derive(Clone)]
+pub(crate) struct AliasRegistry {
+    chain_folder: PathBuf,
+    aliases: Arc<RwLock<BTreeMap<AccountAddress, String>>>,
+}
+
+impl AliasRegistry {
+    pub fn load(chain_folder: &Path, keys: &KeyStore) -> anyhow::Result<Self> {
+        let accounts = keys.accounts();
+        let foundations = accounts
+            .iter()
+            .filter(|account| account.role == AccountRole::Foundation)
+            .count();
+
+        let aliases = ChainSettings::update(chain_folder, |settings| {
+            for account in &accounts {
+                if settings.aliases.contains_key(&account.keys.address) {
+                    continue;
+                }
+                let alias = [default_alias(account, foundations), account.alias.clone()]
+                    .into_iter()
+                    .find(|alias| !settings.aliases.values().any(|taken| taken == alias));
+                if let Some(alias) = alias {
+                    settings.aliases.insert(account.keys.address, alias);
+                }
+            }
+            settings.aliases.clone()
+        })?;
+
+        Ok(AliasRegistry {
+            chain_folder: chain_folder.to_path_buf(),
+            aliases: Arc::new(RwLock::new(aliases)),
+        })
+    }
+
+    pub fn alias(&self, address: &AccountAddress) -> Option<String> {
+        self.aliases.read().unwrap().get(address).cloned()
+    }
+
+    pub fn address(&self, alias: &str) -> Option<AccountAddress> {
+        self.aliases
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(_, name)| name.as_str() == alias)
+            .map(|(address, _)| *address)
+    }
+
+    pub fn all(&self) -> BTreeMap<AccountAddress, String> {
+        self.aliases.read().unwrap().clone()
+    }
+
+    /// The aliases of the given addresses, for responses and events that list accounts.
+    pub fn aliases_of<'a>(
+        &self,
+        addresses: impl IntoIterator<Item = &'a AccountAddress>,
+    ) -> HashMap<AccountAddress, String> {
+        let aliases = self.aliases.read().unwrap();
+        addresses
+            .into_iter()
+            .filter_map(|address| Some((*address, aliases.get(address)?.clone())))
+            .collect()
+    }
+
+    /// Parses an account given either as an address or as an alias.
+    pub fn resolve(&self, account: &str) -> anyhow::Result<AccountAddress> {
+        if let Ok(address) = AccountAddress::from_str(account) {
+            return Ok(address);
+        }
+        self.address(account).ok_or_else(|| {
+            anyhow::anyhow!("{} is neither an account address nor an alias.", account)
+        })
+    }
+
+    /// Names an address, replacing its previous alias.
+    pub fn set(&self, address: AccountAddress, alias: &str) -> anyhow::Result<()> {
+        if alias.is_empty() || AccountAddress::from_str(alias).is_ok() {
+            anyhow::bail!("{} cannot be used as an alias.", alias);
+        }
+        let mut aliases = self.aliases.write().unwrap();
+        if aliases
+            .iter()
+            .any(|(other, name)| name == alias && *other != address)
+        {
+            anyhow::bail!("The alias {} is already in use.", alias);
+        }
+        aliases.insert(address, alias.to_string());
+        self.save(&aliases)
+    }
+
+    pub fn remove(&self, address: &AccountAddress) -> anyhow::Result<()> {
+        let mut aliases = self.aliases.write().unwrap();
+        aliases.remove(address);
+        self.save(&aliases)
+    }
+
+    fn save(&self, aliases: &BTreeMap<AccountAddress, String>) -> anyhow::Result<()> {
+        ChainSettings::update(&self.chain_folder, |settings| {
+            settings.aliases = aliases.clone();
+        })
+    }
+}
+
+// Key files of genesis accounts are named `<template>-<n>.json`, so the number is kept in the default alias.
+fn default_alias(account: &StoredAccount, foundations: usize) -> String {
+    let number = account
+        .alias
+        .rsplit_once('-')
+        .map(|(_, number)| number)
+        .filter(|number| number.parse::<u32>().is_ok());
+    match (account.role, number) {
+        (AccountRole::Foundation, _) if foundations == 1 => "foundation".to_string(),
+        (AccountRole::Foundation, Some(number)) => format!("foundation-{}", number),
+        (AccountRole::Baker, Some(number)) => format!("baker-{}", number),
+        (AccountRole::Plain, Some(number)) => format!("account-{}", number),
+        _ => account.alias.clone(),
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AliasEntry {
+    pub alias: String,
+    pub address: AccountAddress,
+}
+
+#[tauri::command]
+pub(crate) async fn list_aliases(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+) -> Result<Vec<AliasEntry>, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    Ok(chain
+        .aliases
+        .all()
+        .into_iter()
+        .map(|(address, alias)| AliasEntry { alias, address })
+        .collect())
+}
+
+// Names an account, given by address or by its current alias.
+#[tauri::command]
+pub(crate) async fn set_alias(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    account: String,
+    alias: String,
+) -> Result<(), String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let address = chain.aliases.resolve(&account).map_err(|e| e.to_string())?;
+    chain
+        .aliases
+        .set(address, alias.trim())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn remove_alias(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    account: String,
+) -> Result<(), String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let address = chain.aliases.resolve(&account).map_err(|e| e.to_string())?;
+    chain.aliases.remove(&address).map_err(|e| e.to_string())
+}
@@ -0,0 +1,216 @@
+use crate::chain_client::ChainClient;
+use crate::genesis::GenesisConfig;
+use crate::transactions::{expiry, next_nonce, submit_and_wait, TransactionOutcome};
+use crate::{running_chain, AppState, LocalChain};
+use concordium_rust_sdk::smart_contracts::common::Amount;
+use concordium_rust_sdk::types::transactions::{send, BlockItem, ConfigureBakerPayload};
+use concordium_rust_sdk::types::{
+    BakerCredentials, BakerId, BakerKeyPairs, CommissionRanges, OpenStatus, UrlText, WalletAccount,
+};
+use concordium_rust_sdk::v2::{AccountIdentifier, BlockIdentifier, ChainParameters};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RegisteredBaker {
+    pub transaction: TransactionOutcome,
+    pub baker_id: Option<BakerId>,
+    /// The baker credentials file a node needs to bake for the new validator.
+    pub credentials_file: Option<PathBuf>,
+}
+
+/// Sends a `configureBaker` transaction from an account and waits for it to be finalized.
+pub(crate) async fn configure(
+    chain: &ChainClient,
+    sender: &WalletAccount,
+    payload: ConfigureBakerPayload,
+) -> anyhow::Result<TransactionOutcome> {
+    let nonce = next_nonce(chain, &sender.address).await?;
+    let transaction = send::configure_baker(sender, sender.address, nonce, expiry()?, payload);
+    submit_and_wait(chain, BlockItem::AccountTransaction(transaction)).await
+}
+
+/// The commission rates validators may charge, which only exist from protocol version 4.
+async fn commission_bounds(chain: &ChainClient) -> anyhow::Result<CommissionRanges> {
+    let parameters = chain
+        .get()
+        .await?
+        .get_block_chain_parameters(&BlockIdentifier::LastFinal)
+        .await?
+        .response;
+    match parameters {
+        ChainParameters::V0(_) => {
+            anyhow::bail!("Validators can only be configured from protocol version 4.")
+        }
+        ChainParameters::V1(parameters) => Ok(parameters.pool_parameters.commission_bounds),
+        ChainParameters::V2(parameters) => Ok(parameters.pool_parameters.commission_bounds),
+    }
+}
+
+/// Registers an account as a validator with freshly generated baker keys, charging the highest commissions the chain
+/// allows. The keys are written next to the genesis bakers as `baker-<id>-credentials.json`, the id being the index of
+/// the account.
+pub(crate) async fn register(
+    chain: &LocalChain,
+    sender: &WalletAccount,
+    stake: Amount,
+    restake_earnings: bool,
+    open_status: OpenStatus,
+    metadata_url: UrlText,
+) -> anyhow::Result<RegisteredBaker> {
+    let bounds = commission_bounds(&chain.client).await?;
+    let keys = BakerKeyPairs::generate(&mut rand::thread_rng());
+
+    let mut payload = ConfigureBakerPayload::new();
+    payload
+        .set_capital(stake)
+        .set_restake_earnings(restake_earnings)
+        .set_open_for_delegation(open_status)
+        .add_keys(&keys, sender.address, &mut rand::thread_rng())
+        .set_metadata_url(metadata_url)
+        .set_transaction_fee_commission(bounds.transaction.max)
+        .set_baking_reward_commission(bounds.baking.max)
+        .set_finalization_reward_commission(bounds.finalization.max);
+
+    let transaction = configure(&chain.client, sender, payload).await?;
+    if !transaction.success {
+        return Ok(RegisteredBaker {
+            transaction,
+            baker_id: None,
+            credentials_file: None,
+        });
+    }
+
+    let account = chain
+        .client
+        .get()
+        .await?
+        .get_account_info(
+            &AccountIdentifier::Address(sender.address),
+            &BlockIdentifier::LastFinal,
+        )
+        .await?
+        .response;
+    let baker_id = BakerId::from(account.account_index);
+    let credentials_file = GenesisConfig::read(&chain.folder)?
+        .output_path("bakerKeys", "bakers")
+        .join(format!("baker-{}-credentials.json", baker_id));
+    std::fs::write(
+        &credentials_file,
+        serde_json::to_string_pretty(&BakerCredentials::new(baker_id, keys))?,
+    )?;
+    println!(
+        "Registered validator {} with credentials in {}.",
+        baker_id,
+        credentials_file.display()
+    );
+
+    Ok(RegisteredBaker {
+        transaction,
+        baker_id: Some(baker_id),
+        credentials_file: Some(credentials_file),
+    })
+}
+
+fn parse_stake(stake: &str) -> Result<Amount, String> {
+    Amount::from_str(stake).map_err(|e| format!("Invalid amount: {}", e))
+}
+
+// Registers an account of the chain as a validator. `stake` is given in CCD, earnings are restaked unless
+// `restake_earnings` is false and the pool is open for delegation unless another status is given.
+#[tauri::command]
+pub(crate) async fn register_baker(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    account: String,
+    stake: String,
+    restake_earnings: Option<bool>,
+    open_for_delegation: Option<OpenStatus>,
+    metadata_url: Option<String>,
+) -> Result<RegisteredBaker, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let address = chain.aliases.resolve(&account).map_err(|e| e.to_string())?;
+    let sender = chain.keys.signer(&address).map_err(|e| e.to_string())?;
+    let stake = parse_stake(&stake)?;
+    let metadata_url = UrlText::try_from(metadata_url.unwrap_or_default())
+        .map_err(|_| "The metadata URL is too long.".to_string())?;
+
+    register(
+        &chain,
+        &sender,
+        stake,
+        restake_earnings.unwrap_or(true),
+        open_for_delegation.unwrap_or(OpenStatus::OpenForAll),
+        metadata_url,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Changes the stake of a validator, given in CCD, and whether its earnings are restaked. Fields that are not given are
+// left unchanged. A lower stake takes effect after the cooldown period.
+#[tauri::command]
+pub(crate) async fn update_baker_stake(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    account: String,
+    stake: Option<String>,
+    restake_earnings: Option<bool>,
+) -> Result<TransactionOutcome, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let address = chain.aliases.resolve(&account).map_err(|e| e.to_string())?;
+    let sender = chain.keys.signer(&address).map_err(|e| e.to_string())?;
+
+    let mut payload = ConfigureBakerPayload::new();
+    if let Some(stake) = stake {
+        payload.set_capital(parse_stake(&stake)?);
+    }
+    if let Some(restake_earnings) = restake_earnings {
+        payload.set_restake_earnings(restake_earnings);
+    }
+    configure(&chain.client, &sender, payload)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn set_baker_open_status(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    account: String,
+    open_for_delegation: OpenStatus,
+) -> Result<TransactionOutcome, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let address = chain.aliases.resolve(&account).map_err(|e| e.to_string())?;
+    let sender = chain.keys.signer(&address).map_err(|e| e.to_string())?;
+
+    let mut payload = ConfigureBakerPayload::new();
+    payload.set_open_for_delegation(open_for_delegation);
+    configure(&chain.client, &sender, payload)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Removes a validator. Its stake is released after the cooldown period.
+#[tauri::command]
+pub(crate) async fn remove_baker(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    account: String,
+) -> Result<TransactionOutcome, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let address = chain.aliases.resolve(&account).map_err(|e| e.to_string())?;
+    let sender = chain.keys.signer(&address).map_err(|e| e.to_string())?;
+
+    configure(
+        &chain.client,
+        &sender,
+        ConfigureBakerPayload::new_remove_baker(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
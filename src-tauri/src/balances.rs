@@ -0,0 +1,78 @@
+use crate::chain_client::ChainClient;
+use concordium_rust_sdk::smart_contracts::common::{AccountAddress, Amount};
+use concordium_rust_sdk::types::hashes::BlockHash;
+use concordium_rust_sdk::types::{BlockItemSummary, SpecialTransactionOutcome};
+use concordium_rust_sdk::v2::AccountIdentifier;
+use futures::StreamExt;
+use std::collections::{BTreeSet, HashMap};
+
+/// Account balances of a chain, kept up to date block by block.
+///
+/// The full account list is only read once, by [`BalanceTracker::load`]. After that only the accounts
+/// touched by the transactions and special outcomes of a block are queried again.
+pub struct BalanceTracker {
+    balances: HashMap<AccountAddress, Amount>,
+}
+
+impl BalanceTracker {
+    /// Reads the balance of every account at the given block.
+    pub async fn load(chain: &ChainClient, hash: BlockHash) -> anyhow::Result<Self> {
+        println!("Loading account balances at block hash: {:?}", hash);
+
+        let mut client = chain.get().await?;
+        let mut accounts = client.get_account_list(&hash).await?;
+
+        let mut balances = HashMap::new();
+        while let Some(account_addr) = accounts.response.next().await {
+            let account_addr = account_addr?;
+            let info = client
+                .get_account_info(&AccountIdentifier::Address(account_addr), hash)
+                .await?;
+            balances.insert(account_addr, info.response.account_amount);
+        }
+
+        println!("Loaded {} account balances.", balances.len());
+
+        Ok(BalanceTracker { balances })
+    }
+
+    pub fn balances(&self) -> &HashMap<AccountAddress, Amount> {
+        &self.balances
+    }
+
+    /// Updates the balances of the accounts affected by the transaction `summaries` and `special_events`
+    /// of a block, reading them at `hash`, the hash of that block. Returns the balances that changed.
+    pub async fn apply_block(
+        &mut self,
+        chain: &ChainClient,
+        summaries: &[BlockItemSummary],
+        special_events: &[SpecialTransactionOutcome],
+        hash: BlockHash,
+    ) -> anyhow::Result<HashMap<AccountAddress, Amount>> {
+        // Accounts whose balance may have changed, either through a transaction or through a special
+        // outcome such as minting or reward distribution.
+        let affected: BTreeSet<AccountAddress> = summaries
+            .iter()
+            .flat_map(|summary| summary.affected_addresses())
+            .chain(
+                special_events
+                    .iter()
+                    .flat_map(|event| event.affected_addresses()),
+            )
+            .collect();
+
+        let mut client = chain.get().await?;
+        let mut changes = HashMap::new();
+        for account_addr in affected {
+            let info = client
+                .get_account_info(&AccountIdentifier::Address(account_addr), hash)
+                .await?;
+            let amount = info.response.account_amount;
+            if self.balances.insert(account_addr, amount) != Some(amount) {
+                changes.insert(account_addr, amount);
+            }
+        }
+
+        Ok(changes)
+    }
+}
@@ -0,0 +1,83 @@
+use concordium_rust_sdk::endpoints::Endpoint;
+use concordium_rust_sdk::v2;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::Duration;
+
+/// gRPC endpoint of the node launched by LC1C.
+pub const NODE_GRPC_ENDPOINT: &str = "http://127.0.0.1:20100";
+
+/// How long dialing the node may take before the attempt fails.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long the node may take to answer a query. Streams only wait this long for the node to start responding, so
+/// following finalized blocks is not cut off while the chain produces none.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Handle to the gRPC API of a running local chain.
+///
+/// The handle is cheap to clone and is shared between the indexer tasks and the commands of a chain.
+/// The connection is dialed lazily and dropped with [`ChainClient::reset`] when the node goes away,
+/// so the next query reconnects once the node is back up. Once the chain is killed the handle is
+/// [closed](ChainClient::close), which stops the background tasks using it.
+#[derive(Clone)]
+pub struct ChainClient {
+    endpoint: Endpoint,
+    client: Arc<Mutex<Option<v2::Client>>>,
+    closed: Arc<AtomicBool>,
+}
+
+impl ChainClient {
+    pub fn new(endpoint: &str) -> anyhow::Result<Self> {
+        Ok(ChainClient {
+            endpoint: Endpoint::from_str(endpoint)?
+                .connect_timeout(CONNECT_TIMEOUT)
+                .timeout(REQUEST_TIMEOUT),
+            client: Arc::new(Mutex::new(None)),
+            closed: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Returns a connected client, dialing the node if there is no live connection.
+    pub async fn get(&self) -> anyhow::Result<v2::Client> {
+        let mut client = self.client.lock().await;
+        if let Some(client) = client.as_ref() {
+            return Ok(client.clone());
+        }
+
+        let connected = v2::Client::new(self.endpoint.clone()).await?;
+        *client = Some(connected.clone());
+        Ok(connected)
+    }
+
+    /// Waits until the node accepts connections. The node takes a few seconds to come up after launch.
+    /// Returns `None` once the handle is closed.
+    pub async fn connected(&self) -> Option<v2::Client> {
+        while !self.is_closed() {
+            match self.get().await {
+                Ok(client) => return Some(client),
+                Err(e) => {
+                    eprintln!("Failed to connect to the node: {}. Retrying...", e);
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+        None
+    }
+
+    /// Marks the chain as stopped.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    /// Drops the current connection, so the next query dials the node again.
+    pub async fn reset(&self) {
+        self.client.lock().await.take();
+    }
+}
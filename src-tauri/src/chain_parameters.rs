@@ -0,0 +1,185 @@
+use crate::transactions::TransactionOutcome;
+use crate::updates::submit_update;
+use crate::{running_chain, AppState, LocalChain};
+use concordium_rust_sdk::types::{
+    AmountFraction, Energy, ExchangeRate, PoolParameters, TransactionFeeDistribution, UpdatePayload,
+};
+use concordium_rust_sdk::v2::{BlockIdentifier, ChainParameters};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// A chain parameter update, tagged by `parameter`.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "parameter", rename_all = "camelCase")]
+pub(crate) enum ChainParameterUpdate {
+    EuroPerEnergy {
+        numerator: u64,
+        denominator: u64,
+    },
+    MicroCcdPerEuro {
+        numerator: u64,
+        denominator: u64,
+    },
+    /// Fractions of the transaction fees going to the baker and the GAS account. The rest goes to the foundation.
+    #[serde(rename_all = "camelCase")]
+    TransactionFeeDistribution {
+        baker: AmountFraction,
+        gas_account: AmountFraction,
+    },
+    /// Commissions of passive delegation, commission bounds and capital limits of pools, from protocol version 4.
+    PoolParameters(PoolParameters),
+    /// Maximum energy of the transactions in a block, from protocol version 6.
+    BlockEnergyLimit {
+        energy: u64,
+    },
+}
+
+/// The chain parameters LC1C can update, as of the last finalized block.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ChainParameterValues {
+    pub euro_per_energy: ExchangeRate,
+    pub micro_ccd_per_euro: ExchangeRate,
+    pub transaction_fee_distribution: TransactionFeeDistribution,
+    /// Only exists from protocol version 4.
+    pub pool_parameters: Option<PoolParameters>,
+    /// Only updatable from protocol version 6. Before that it is fixed in the genesis.
+    pub block_energy_limit: Option<Energy>,
+}
+
+impl From<ChainParameters> for ChainParameterValues {
+    fn from(parameters: ChainParameters) -> Self {
+        match parameters {
+            ChainParameters::V0(parameters) => ChainParameterValues {
+                euro_per_energy: parameters.euro_per_energy,
+                micro_ccd_per_euro: parameters.micro_ccd_per_euro,
+                transaction_fee_distribution: parameters.transaction_fee_distribution,
+                pool_parameters: None,
+                block_energy_limit: None,
+            },
+            ChainParameters::V1(parameters) => ChainParameterValues {
+                euro_per_energy: parameters.euro_per_energy,
+                micro_ccd_per_euro: parameters.micro_ccd_per_euro,
+                transaction_fee_distribution: parameters.transaction_fee_distribution,
+                pool_parameters: Some(parameters.pool_parameters),
+                block_energy_limit: None,
+            },
+            ChainParameters::V2(parameters) => ChainParameterValues {
+                euro_per_energy: parameters.euro_per_energy,
+                micro_ccd_per_euro: parameters.micro_ccd_per_euro,
+                transaction_fee_distribution: parameters.transaction_fee_distribution,
+                pool_parameters: Some(parameters.pool_parameters),
+                block_energy_limit: Some(parameters.block_energy_limit),
+            },
+        }
+    }
+}
+
+pub(crate) async fn chain_parameters(chain: &LocalChain) -> anyhow::Result<ChainParameterValues> {
+    let parameters = chain
+        .client
+        .get()
+        .await?
+        .get_block_chain_parameters(&BlockIdentifier::LastFinal)
+        .await?
+        .response;
+    Ok(parameters.into())
+}
+
+fn exchange_rate(numerator: u64, denominator: u64) -> anyhow::Result<ExchangeRate> {
+    ExchangeRate::new(numerator, denominator)
+        .ok_or_else(|| anyhow::anyhow!("Exchange rates must be positive."))
+}
+
+/// Submits a chain parameter update signed with the chain's update keys. `effective_time` is a Unix timestamp in
+/// seconds.
+pub(crate) async fn update_parameter(
+    chain: &LocalChain,
+    update: ChainParameterUpdate,
+    effective_time: Option<u64>,
+) -> anyhow::Result<TransactionOutcome> {
+    let current = chain_parameters(chain).await?;
+    let sequence_numbers = chain
+        .client
+        .get()
+        .await?
+        .get_next_update_sequence_numbers(&BlockIdentifier::LastFinal)
+        .await?
+        .response;
+
+    let (name, sequence_number, payload) = match update {
+        ChainParameterUpdate::EuroPerEnergy {
+            numerator,
+            denominator,
+        } => (
+            "euroPerEnergy",
+            sequence_numbers.euro_per_energy,
+            UpdatePayload::EuroPerEnergy(exchange_rate(numerator, denominator)?),
+        ),
+        ChainParameterUpdate::MicroCcdPerEuro {
+            numerator,
+            denominator,
+        } => (
+            "microCcdPerEuro",
+            sequence_numbers.micro_ccd_per_euro,
+            UpdatePayload::MicroGTUPerEuro(exchange_rate(numerator, denominator)?),
+        ),
+        ChainParameterUpdate::TransactionFeeDistribution { baker, gas_account } => (
+            "transactionFeeDistribution",
+            sequence_numbers.transaction_fee_distribution,
+            UpdatePayload::TransactionFeeDistribution(TransactionFeeDistribution {
+                baker,
+                gas_account,
+            }),
+        ),
+        ChainParameterUpdate::PoolParameters(pool_parameters) => {
+            if current.pool_parameters.is_none() {
+                anyhow::bail!("Pool parameters only exist from protocol version 4.");
+            }
+            (
+                "poolParameters",
+                sequence_numbers.pool_parameters,
+                UpdatePayload::PoolParametersCPV1(pool_parameters),
+            )
+        }
+        ChainParameterUpdate::BlockEnergyLimit { energy } => {
+            if current.block_energy_limit.is_none() {
+                anyhow::bail!(
+                    "The block energy limit can only be updated from protocol version 6."
+                );
+            }
+            (
+                "blockEnergyLimit",
+                sequence_numbers.block_energy_limit,
+                UpdatePayload::BlockEnergyLimitCPV2(Energy::from(energy)),
+            )
+        }
+    };
+
+    submit_update(chain, name, sequence_number, effective_time, payload, None).await
+}
+
+// Changes a chain parameter of a running chain, signing with the update keys in the chain folder. `effective_time` is a
+// Unix timestamp in seconds and defaults to a minute from now.
+#[tauri::command]
+pub(crate) async fn update_chain_parameter(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    update: ChainParameterUpdate,
+    effective_time: Option<u64>,
+) -> Result<TransactionOutcome, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    update_parameter(&chain, update, effective_time)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn get_chain_parameters(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+) -> Result<ChainParameterValues, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    chain_parameters(&chain).await.map_err(|e| e.to_string())
+}
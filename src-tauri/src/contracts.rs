@@ -0,0 +1,608 @@
+use crate::chain_client::ChainClient;
+use crate::schema::{decode, encode_parameter, SchemaCache};
+use crate::transactions::{expiry, next_nonce, submit_and_wait, TransactionOutcome};
+use crate::{running_chain, AppState};
+use concordium_rust_sdk::common;
+use concordium_rust_sdk::smart_contracts::common::schema::VersionedModuleSchema;
+use concordium_rust_sdk::smart_contracts::common::{
+    Address, Amount, ContractAddress, OwnedContractName, OwnedReceiveName,
+};
+use concordium_rust_sdk::types::smart_contracts::{
+    ContractContext, InstanceInfo, InvokeContractResult, ModuleReference, OwnedParameter,
+    WasmModule,
+};
+use concordium_rust_sdk::types::transactions::{
+    send, BlockItem, InitContractPayload, UpdateContractPayload,
+};
+use concordium_rust_sdk::types::{ContractTraceElement, Energy, RejectReason, WalletAccount};
+use concordium_rust_sdk::v2::BlockIdentifier;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Energy for initializing a contract unless another limit is given. The node cannot simulate `init` functions, so this
+/// is not estimated.
+const INIT_ENERGY: u64 = 30_000;
+/// Energy limit when simulating an update.
+const INVOKE_ENERGY: u64 = 3_000_000;
+/// Percentage added to the energy a simulated update used, in case the state changes before the update is executed.
+const ENERGY_MARGIN_PERCENT: u64 = 10;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DeployOutcome {
+    pub module_reference: String,
+    /// Whether the module was already on chain, in which case no transaction was sent.
+    pub already_deployed: bool,
+    pub transaction: Option<TransactionOutcome>,
+}
+
+/// Reads a versioned module, i.e. the `.wasm.v1` file produced by `cargo concordium build`.
+pub(crate) fn read_module(wasm_path: &Path) -> anyhow::Result<WasmModule> {
+    let bytes = std::fs::read(wasm_path)?;
+    common::from_bytes(&mut std::io::Cursor::new(bytes)).map_err(|e| {
+        anyhow::anyhow!(
+            "{} is not a versioned Wasm module: {}",
+            wasm_path.display(),
+            e
+        )
+    })
+}
+
+pub(crate) async fn is_deployed(
+    chain: &ChainClient,
+    module_ref: &ModuleReference,
+) -> anyhow::Result<bool> {
+    let mut client = chain.get().await?;
+    match client
+        .get_module_source(module_ref, &BlockIdentifier::LastFinal)
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(e) if e.is_not_found() => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub(crate) async fn deploy(
+    chain: &ChainClient,
+    sender: &WalletAccount,
+    module: WasmModule,
+) -> anyhow::Result<DeployOutcome> {
+    let module_ref = module.get_module_ref();
+    if is_deployed(chain, &module_ref).await? {
+        println!("Module {} is already deployed.", module_ref);
+        return Ok(DeployOutcome {
+            module_reference: module_ref.to_string(),
+            already_deployed: true,
+            transaction: None,
+        });
+    }
+
+    let nonce = next_nonce(chain, &sender.address).await?;
+    let transaction = send::deploy_module(sender, sender.address, nonce, expiry()?, module);
+    let outcome = submit_and_wait(chain, BlockItem::AccountTransaction(transaction)).await?;
+    if let Some(reason) = outcome.summary.is_rejected_account_transaction() {
+        anyhow::bail!("Deploying module {} was rejected: {:?}", module_ref, reason);
+    }
+
+    Ok(DeployOutcome {
+        module_reference: module_ref.to_string(),
+        already_deployed: false,
+        transaction: Some(outcome),
+    })
+}
+
+// Deploys a smart contract module built with `cargo concordium build`, sent from one of the chain's accounts.
+#[tauri::command]
+pub(crate) async fn deploy_module(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    sender: String,
+    wasm_path: String,
+) -> Result<DeployOutcome, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let sender = chain.aliases.resolve(&sender).map_err(|e| e.to_string())?;
+    let sender = chain.keys.signer(&sender).map_err(|e| e.to_string())?;
+    let module = read_module(Path::new(&wasm_path)).map_err(|e| e.to_string())?;
+
+    deploy(&chain.client, &sender, module)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ContractOutcome {
+    pub contract_address: ContractAddress,
+    /// Logged events, decoded with the contract's event schema when it has one.
+    pub events: Vec<JsonValue>,
+    /// Return value of an update, taken from simulating it right before it was sent.
+    pub return_value: Option<JsonValue>,
+    pub transaction: TransactionOutcome,
+}
+
+/// Parses a contract address given either as `<index,subindex>` or as just the index.
+pub(crate) fn parse_contract_address(address: &str) -> anyhow::Result<ContractAddress> {
+    let address = address.trim();
+    let (index, subindex) = match address
+        .strip_prefix('<')
+        .and_then(|address| address.strip_suffix('>'))
+    {
+        Some(address) => address
+            .split_once(',')
+            .ok_or_else(|| anyhow::anyhow!("Invalid contract address: {}", address))?,
+        None => (address, "0"),
+    };
+    Ok(ContractAddress::new(
+        index.trim().parse()?,
+        subindex.trim().parse()?,
+    ))
+}
+
+fn parse_amount(amount: Option<String>) -> Result<Amount, String> {
+    match amount {
+        Some(amount) => Amount::from_str(&amount).map_err(|e| format!("Invalid amount: {}", e)),
+        None => Ok(Amount::zero()),
+    }
+}
+
+/// The module of a contract instance and the name of the contract in it, as of the given block.
+pub(crate) async fn instance_contract(
+    chain: &ChainClient,
+    address: ContractAddress,
+    block: &BlockIdentifier,
+) -> anyhow::Result<(ModuleReference, String)> {
+    let mut client = chain.get().await?;
+    let info = client.get_instance_info(address, block).await?.response;
+    let (name, source_module) = match info {
+        InstanceInfo::V0 {
+            name,
+            source_module,
+            ..
+        }
+        | InstanceInfo::V1 {
+            name,
+            source_module,
+            ..
+        } => (name, source_module),
+    };
+    Ok((
+        source_module,
+        name.as_contract_name().contract_name().to_string(),
+    ))
+}
+
+pub(crate) async fn invoke(
+    chain: &ChainClient,
+    context: &ContractContext,
+) -> anyhow::Result<InvokeContractResult> {
+    let mut client = chain.get().await?;
+    let result = client
+        .invoke_instance(&BlockIdentifier::LastFinal, context)
+        .await?
+        .response;
+    Ok(result)
+}
+
+/// Initializes a contract with the given energy limit, or [`INIT_ENERGY`] if there is none.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn init(
+    chain: &ChainClient,
+    schemas: &SchemaCache,
+    sender: &WalletAccount,
+    module_ref: ModuleReference,
+    contract_name: &str,
+    param: &JsonValue,
+    amount: Amount,
+    energy: Option<u64>,
+) -> anyhow::Result<ContractOutcome> {
+    let schema = schemas.get(&module_ref).await?;
+    let param_schema = schema
+        .as_ref()
+        .and_then(|schema| schema.get_init_param_schema(contract_name).ok());
+    let param = encode_parameter(param_schema.as_ref(), param)?;
+    let init_name = OwnedContractName::new(format!("init_{}", contract_name))
+        .map_err(|e| anyhow::anyhow!("Invalid contract name {}: {:?}", contract_name, e))?;
+
+    let payload = InitContractPayload {
+        amount,
+        mod_ref: module_ref,
+        init_name,
+        param,
+    };
+    let nonce = next_nonce(chain, &sender.address).await?;
+    let transaction = send::init_contract(
+        sender,
+        sender.address,
+        nonce,
+        expiry()?,
+        payload,
+        Energy::from(energy.unwrap_or(INIT_ENERGY)),
+    );
+    let outcome = submit_and_wait(chain, BlockItem::AccountTransaction(transaction)).await?;
+    if let Some(reason) = outcome.summary.is_rejected_account_transaction() {
+        anyhow::bail!("Initializing {} was rejected: {:?}", contract_name, reason);
+    }
+
+    let initialized = outcome
+        .summary
+        .contract_init()
+        .ok_or_else(|| anyhow::anyhow!("The transaction did not initialize a contract."))?;
+    let event_schema = schema
+        .as_ref()
+        .and_then(|schema| schema.get_event_schema(contract_name).ok());
+    let contract_address = initialized.address;
+    let events = initialized
+        .events
+        .iter()
+        .map(|event| decode(event_schema.as_ref(), event.as_ref()))
+        .collect();
+
+    Ok(ContractOutcome {
+        contract_address,
+        events,
+        return_value: None,
+        transaction: outcome,
+    })
+}
+
+pub(crate) async fn update(
+    chain: &ChainClient,
+    schemas: &SchemaCache,
+    sender: &WalletAccount,
+    address: ContractAddress,
+    entrypoint: &str,
+    param: &JsonValue,
+    amount: Amount,
+) -> anyhow::Result<ContractOutcome> {
+    let (module_ref, contract_name) =
+        instance_contract(chain, address, &BlockIdentifier::LastFinal).await?;
+    let schema = schemas.get(&module_ref).await?;
+    let param_schema = schema.as_ref().and_then(|schema| {
+        schema
+            .get_receive_param_schema(&contract_name, entrypoint)
+            .ok()
+    });
+    let param = encode_parameter(param_schema.as_ref(), param)?;
+    send_update(
+        chain,
+        sender,
+        address,
+        &contract_name,
+        schema.as_deref(),
+        entrypoint,
+        param,
+        amount,
+    )
+    .await
+}
+
+/// Like [`update`], but with a parameter that is already serialized, e.g. one not described by the contract's schema.
+pub(crate) async fn update_with_parameter(
+    chain: &ChainClient,
+    schemas: &SchemaCache,
+    sender: &WalletAccount,
+    address: ContractAddress,
+    entrypoint: &str,
+    param: OwnedParameter,
+    amount: Amount,
+) -> anyhow::Result<ContractOutcome> {
+    let (module_ref, contract_name) =
+        instance_contract(chain, address, &BlockIdentifier::LastFinal).await?;
+    let schema = schemas.get(&module_ref).await?;
+    send_update(
+        chain,
+        sender,
+        address,
+        &contract_name,
+        schema.as_deref(),
+        entrypoint,
+        param,
+        amount,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn send_update(
+    chain: &ChainClient,
+    sender: &WalletAccount,
+    address: ContractAddress,
+    contract_name: &str,
+    schema: Option<&VersionedModuleSchema>,
+    entrypoint: &str,
+    param: OwnedParameter,
+    amount: Amount,
+) -> anyhow::Result<ContractOutcome> {
+    let receive_name = OwnedReceiveName::new(format!("{}.{}", contract_name, entrypoint))
+        .map_err(|e| anyhow::anyhow!("Invalid entrypoint {}: {:?}", entrypoint, e))?;
+
+    // Simulate the update first, to estimate its energy and to get its return value, which transactions do not record.
+    let context = ContractContext {
+        invoker: Some(Address::Account(sender.address)),
+        contract: address,
+        amount,
+        method: receive_name.clone(),
+        parameter: param.clone(),
+        energy: Energy::from(INVOKE_ENERGY),
+    };
+    let (return_value, used_energy) = match invoke(chain, &context).await? {
+        InvokeContractResult::Success {
+            return_value,
+            used_energy,
+            ..
+        } => (return_value, used_energy),
+        InvokeContractResult::Failure { reason, .. } => {
+            anyhow::bail!("The update would be rejected: {:?}", reason)
+        }
+    };
+
+    let payload = UpdateContractPayload {
+        amount,
+        address,
+        receive_name,
+        message: param,
+    };
+    let energy = used_energy.energy + used_energy.energy * ENERGY_MARGIN_PERCENT / 100;
+    let nonce = next_nonce(chain, &sender.address).await?;
+    let transaction = send::update_contract(
+        sender,
+        sender.address,
+        nonce,
+        expiry()?,
+        payload,
+        Energy::from(energy),
+    );
+    let outcome = submit_and_wait(chain, BlockItem::AccountTransaction(transaction)).await?;
+    if let Some(reason) = outcome.summary.is_rejected_account_transaction() {
+        anyhow::bail!("Updating {} was rejected: {:?}", address, reason);
+    }
+
+    let event_schema = schema.and_then(|schema| schema.get_event_schema(contract_name).ok());
+    let events = outcome
+        .summary
+        .contract_update_logs()
+        .into_iter()
+        .flatten()
+        .flat_map(|(logged_by, events)| {
+            // Events of other contracts called along the way are not decoded with this contract's schema.
+            let event_schema = if logged_by == address {
+                event_schema.as_ref()
+            } else {
+                None
+            };
+            events
+                .iter()
+                .map(move |event| decode(event_schema, event.as_ref()))
+        })
+        .collect();
+    let return_value_schema = schema.and_then(|schema| {
+        schema
+            .get_receive_return_value_schema(contract_name, entrypoint)
+            .ok()
+    });
+    let return_value =
+        return_value.map(|return_value| decode(return_value_schema.as_ref(), &return_value.value));
+
+    Ok(ContractOutcome {
+        contract_address: address,
+        events,
+        return_value,
+        transaction: outcome,
+    })
+}
+
+// Initializes a contract from a deployed module. `entrypoint` is the contract name, with or without the `init_` prefix,
+// and `param` the JSON parameter, which is encoded with the module's schema. `energy` is the energy limit of the
+// transaction and defaults to 30 000 NRG, which contracts with a costly `init` function may exceed.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn init_contract(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    sender: String,
+    module_ref: String,
+    entrypoint: String,
+    param: Option<JsonValue>,
+    amount: Option<String>,
+    energy: Option<u64>,
+) -> Result<ContractOutcome, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let sender = chain.aliases.resolve(&sender).map_err(|e| e.to_string())?;
+    let sender = chain.keys.signer(&sender).map_err(|e| e.to_string())?;
+    let module_ref = ModuleReference::from_str(&module_ref).map_err(|e| e.to_string())?;
+    let contract_name = entrypoint.strip_prefix("init_").unwrap_or(&entrypoint);
+    let amount = parse_amount(amount)?;
+
+    init(
+        &chain.client,
+        &chain.schemas,
+        &sender,
+        module_ref,
+        contract_name,
+        &param.unwrap_or(JsonValue::Null),
+        amount,
+        energy,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Calls a receive function of a contract instance. The energy is estimated by simulating the call first.
+#[tauri::command]
+pub(crate) async fn update_contract(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    sender: String,
+    address: String,
+    entrypoint: String,
+    param: Option<JsonValue>,
+    amount: Option<String>,
+) -> Result<ContractOutcome, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let sender = chain.aliases.resolve(&sender).map_err(|e| e.to_string())?;
+    let sender = chain.keys.signer(&sender).map_err(|e| e.to_string())?;
+    let address = parse_contract_address(&address).map_err(|e| e.to_string())?;
+    let amount = parse_amount(amount)?;
+
+    update(
+        &chain.client,
+        &chain.schemas,
+        &sender,
+        address,
+        &entrypoint,
+        &param.unwrap_or(JsonValue::Null),
+        amount,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct InvokeOutcome {
+    pub success: bool,
+    pub return_value: Option<JsonValue>,
+    /// Error value of a rejected call, decoded with the entrypoint's error schema when it has one.
+    pub error: Option<JsonValue>,
+    pub reject_reason: Option<RejectReason>,
+    /// Events the contract would log.
+    pub events: Vec<JsonValue>,
+    pub used_energy: Energy,
+}
+
+/// Simulates calling a receive function against the last finalized state, without sending a transaction.
+pub(crate) async fn dry_run(
+    chain: &ChainClient,
+    schemas: &SchemaCache,
+    address: ContractAddress,
+    entrypoint: &str,
+    param: &JsonValue,
+    invoker: Option<Address>,
+    amount: Amount,
+) -> anyhow::Result<InvokeOutcome> {
+    let (module_ref, contract_name) =
+        instance_contract(chain, address, &BlockIdentifier::LastFinal).await?;
+    let schema = schemas.get(&module_ref).await?;
+    let param_schema = schema.as_ref().and_then(|schema| {
+        schema
+            .get_receive_param_schema(&contract_name, entrypoint)
+            .ok()
+    });
+    let param = encode_parameter(param_schema.as_ref(), param)?;
+    let method = OwnedReceiveName::new(format!("{}.{}", contract_name, entrypoint))
+        .map_err(|e| anyhow::anyhow!("Invalid entrypoint {}: {:?}", entrypoint, e))?;
+
+    let context = ContractContext {
+        invoker,
+        contract: address,
+        amount,
+        method,
+        parameter: param,
+        energy: Energy::from(INVOKE_ENERGY),
+    };
+    let outcome = match invoke(chain, &context).await? {
+        InvokeContractResult::Success {
+            return_value,
+            events,
+            used_energy,
+            ..
+        } => {
+            let event_schema = schema
+                .as_ref()
+                .and_then(|schema| schema.get_event_schema(&contract_name).ok());
+            let return_value_schema = schema.as_ref().and_then(|schema| {
+                schema
+                    .get_receive_return_value_schema(&contract_name, entrypoint)
+                    .ok()
+            });
+            InvokeOutcome {
+                success: true,
+                return_value: return_value
+                    .map(|return_value| decode(return_value_schema.as_ref(), &return_value.value)),
+                error: None,
+                reject_reason: None,
+                events: events
+                    .iter()
+                    .flat_map(|element| match element {
+                        ContractTraceElement::Updated { data } if data.address == address => {
+                            data.events.as_slice()
+                        }
+                        ContractTraceElement::Interrupted {
+                            address: from,
+                            events,
+                        } if *from == address => events.as_slice(),
+                        _ => &[],
+                    })
+                    .map(|event| decode(event_schema.as_ref(), event.as_ref()))
+                    .collect(),
+                used_energy,
+            }
+        }
+        InvokeContractResult::Failure {
+            return_value,
+            reason,
+            used_energy,
+            ..
+        } => {
+            let error_schema = schema.as_ref().and_then(|schema| {
+                schema
+                    .get_receive_error_schema(&contract_name, entrypoint)
+                    .ok()
+            });
+            InvokeOutcome {
+                success: false,
+                return_value: None,
+                error: return_value.map(|error| decode(error_schema.as_ref(), &error.value)),
+                reject_reason: Some(reason),
+                events: Vec::new(),
+                used_energy,
+            }
+        }
+    };
+
+    Ok(outcome)
+}
+
+// Calls a receive function without submitting a transaction, e.g. to read a view function. `invoker` is an account or
+// contract address and defaults to no invoker.
+#[tauri::command]
+pub(crate) async fn invoke_contract(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    address: String,
+    entrypoint: String,
+    json_param: Option<JsonValue>,
+    invoker: Option<String>,
+    amount: Option<String>,
+) -> Result<InvokeOutcome, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let address = parse_contract_address(&address).map_err(|e| e.to_string())?;
+    let invoker = match invoker {
+        Some(invoker) => Some(match chain.aliases.resolve(&invoker) {
+            Ok(account) => Address::Account(account),
+            Err(_) => Address::Contract(
+                parse_contract_address(&invoker)
+                    .map_err(|_| format!("Invalid invoker address: {}", invoker))?,
+            ),
+        }),
+        None => None,
+    };
+    let amount = parse_amount(amount)?;
+
+    dry_run(
+        &chain.client,
+        &chain.schemas,
+        address,
+        &entrypoint,
+        &json_param.unwrap_or(JsonValue::Null),
+        invoker,
+        amount,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,210 @@
+use crate::genesis::GenesisConfig;
+use crate::keystore::AccountEntry;
+use crate::transactions::{expiry, submit_and_wait, transfer, TransactionOutcome};
+use crate::{running_chain, AppState, LocalChain};
+use concordium_rust_sdk::common::types::{KeyIndex, KeyPair, TransactionTime};
+use concordium_rust_sdk::common::Versioned;
+use concordium_rust_sdk::id::account_holder::build_pub_info_for_ip;
+use concordium_rust_sdk::id::constants::{ArCurve, AttributeKind, IpPairing};
+use concordium_rust_sdk::id::dodis_yampolskiy_prf as prf;
+use concordium_rust_sdk::id::identity_provider::create_initial_cdi;
+use concordium_rust_sdk::id::pedersen_commitment::Value as PedersenValue;
+use concordium_rust_sdk::id::types::{
+    account_address_from_registration_id, AccountCredential, AccountCredentialMessage, AccountKeys,
+    AttributeList, InitialAccountData, IpData, SignatureThreshold, YearMonth,
+};
+use concordium_rust_sdk::smart_contracts::common::Amount;
+use concordium_rust_sdk::types::transactions::BlockItem;
+use concordium_rust_sdk::types::WalletAccount;
+use concordium_rust_sdk::v2::BlockIdentifier;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// How long the credentials of created accounts are valid, in years.
+const CREDENTIAL_VALIDITY_YEARS: u16 = 5;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CreatedAccount {
+    pub account: AccountEntry,
+    pub deployment: TransactionOutcome,
+    /// The transfer of the initial funding from the foundation account, if any was requested.
+    pub funding: Option<TransactionOutcome>,
+    /// Why the initial funding could not be sent. The account exists and its keys are stored regardless.
+    pub funding_error: Option<String>,
+}
+
+// Identity providers are written by the genesis creator as `IpData`, either bare or versioned.
+fn read_identity_provider(path: &Path) -> anyhow::Result<IpData<IpPairing>> {
+    let contents = std::fs::read_to_string(path)?;
+    match serde_json::from_str::<Versioned<IpData<IpPairing>>>(&contents) {
+        Ok(versioned) => Ok(versioned.value),
+        Err(_) => Ok(serde_json::from_str(&contents)?),
+    }
+}
+
+/// The first identity provider in the chain folder for which the private keys are available.
+fn local_identity_provider(chain_folder: &Path) -> anyhow::Result<IpData<IpPairing>> {
+    let idps_folder = GenesisConfig::read(chain_folder)?.output_path("identityProviders", "idps");
+
+    let mut paths: Vec<_> = std::fs::read_dir(&idps_folder)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .find_map(|path| read_identity_provider(path).ok())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "No identity provider keys found in {}.",
+                idps_folder.display()
+            )
+        })
+}
+
+/// Creates a new account with a single key through an initial credential signed by one of the chain's own identity
+/// providers. Initial credentials reveal no attributes and are not shared with the anonymity revokers, so only the
+/// identity provider keys are needed.
+pub(crate) async fn create(
+    chain: &LocalChain,
+    alias: &str,
+) -> anyhow::Result<(WalletAccount, TransactionOutcome)> {
+    let ip_data = local_identity_provider(&chain.folder)?;
+    let global_context = chain
+        .client
+        .get()
+        .await?
+        .get_cryptographic_parameters(&BlockIdentifier::LastFinal)
+        .await?
+        .response;
+
+    let mut csprng = rand::thread_rng();
+    let initial_account = InitialAccountData {
+        keys: BTreeMap::from([(KeyIndex(0), KeyPair::generate(&mut csprng))]),
+        threshold: SignatureThreshold::ONE,
+    };
+    let id_cred_sec = PedersenValue::<ArCurve>::generate(&mut csprng);
+    let prf_key = prf::SecretKey::<ArCurve>::generate(&mut csprng);
+    let pub_info_for_ip =
+        build_pub_info_for_ip(&global_context, &id_cred_sec, &prf_key, &initial_account)
+            .ok_or_else(|| anyhow::anyhow!("Failed to derive the credential registration ID."))?;
+
+    let created_at = YearMonth::now();
+    let valid_to = YearMonth::new(
+        created_at.year + CREDENTIAL_VALIDITY_YEARS,
+        created_at.month,
+    )
+    .ok_or_else(|| anyhow::anyhow!("Invalid credential expiry."))?;
+    let attributes = AttributeList::<_, AttributeKind> {
+        valid_to,
+        created_at,
+        max_accounts: 1,
+        alist: BTreeMap::new(),
+        _phantom: Default::default(),
+    };
+
+    let message_expiry: TransactionTime = expiry()?;
+    let icdi = create_initial_cdi(
+        &ip_data.public_ip_info,
+        pub_info_for_ip,
+        &attributes,
+        message_expiry,
+        &ip_data.ip_cdi_secret_key,
+    );
+    let address = account_address_from_registration_id(&icdi.values.reg_id);
+
+    let message = AccountCredentialMessage {
+        message_expiry,
+        credential: AccountCredential::Initial { icdi },
+    };
+    let outcome = submit_and_wait(
+        &chain.client,
+        BlockItem::CredentialDeployment(Box::new(message)),
+    )
+    .await?;
+    if !outcome.success {
+        anyhow::bail!("The credential deployment of {} failed.", alias);
+    }
+
+    let keys = WalletAccount {
+        address,
+        keys: AccountKeys::from(initial_account),
+    };
+    Ok((keys, outcome))
+}
+
+// Creates an account on a running chain and adds its keys to the chain's key store under `alias`. `initial_funding` is
+// an amount in CCD sent from the foundation account once the account exists.
+#[tauri::command]
+pub(crate) async fn create_account(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    alias: String,
+    initial_funding: Option<String>,
+) -> Result<CreatedAccount, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    if alias.is_empty()
+        || !alias
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err("Account aliases may only contain letters, digits, '-' and '_'.".to_string());
+    }
+    if chain.keys.by_alias(&alias).is_some() || chain.aliases.address(&alias).is_some() {
+        return Err(format!("An account named {} already exists.", alias));
+    }
+    let initial_funding = match initial_funding {
+        Some(amount) => {
+            Some(Amount::from_str(&amount).map_err(|e| format!("Invalid amount: {}", e))?)
+        }
+        None => None,
+    };
+
+    let (keys, deployment) = create(&chain, &alias).await.map_err(|e| e.to_string())?;
+    let address = keys.address;
+    let stored = chain.keys.add(&alias, keys).map_err(|e| e.to_string())?;
+    chain
+        .aliases
+        .set(address, &alias)
+        .map_err(|e| e.to_string())?;
+
+    // The account already exists at this point, so a failed funding is reported along with it rather than as an error.
+    let (funding, funding_error) = match initial_funding {
+        Some(amount) if amount > Amount::zero() => match chain.keys.foundation() {
+            Some(foundation) => {
+                match transfer(&chain.client, &foundation.keys, address, amount, None).await {
+                    Ok(outcome) => (Some(outcome), None),
+                    Err(e) => (None, Some(e.to_string())),
+                }
+            }
+            None => (
+                None,
+                Some("The chain folder holds no keys of a foundation account.".to_string()),
+            ),
+        },
+        _ => (None, None),
+    };
+    let funded = funding.as_ref().map_or(false, |outcome| outcome.success);
+
+    Ok(CreatedAccount {
+        account: AccountEntry {
+            address,
+            alias: stored.alias,
+            role: stored.role,
+            balance: Some(
+                initial_funding
+                    .filter(|_| funded)
+                    .unwrap_or_else(Amount::zero),
+            ),
+        },
+        deployment,
+        funding,
+        funding_error,
+    })
+}
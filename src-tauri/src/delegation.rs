@@ -0,0 +1,206 @@
+use crate::chain_client::ChainClient;
+use crate::transactions::{expiry, next_nonce, submit_and_wait, TransactionOutcome};
+use crate::{running_chain, AppState, LocalChain};
+use concordium_rust_sdk::smart_contracts::common::{AccountAddress, Amount};
+use concordium_rust_sdk::types::transactions::{send, BlockItem, ConfigureDelegationPayload};
+use concordium_rust_sdk::types::{
+    AccountIndex, BakerId, CommissionRates, DelegationTarget, OpenStatus, WalletAccount,
+};
+use concordium_rust_sdk::v2::{AccountIdentifier, BlockIdentifier};
+use futures::StreamExt;
+use serde::Serialize;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// A validator's pool, as reported by the node at the last finalized block.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PoolEntry {
+    pub baker_id: BakerId,
+    pub baker_address: AccountAddress,
+    pub alias: Option<String>,
+    pub open_status: OpenStatus,
+    pub equity_capital: Amount,
+    pub delegated_capital: Amount,
+    /// The most that can be delegated to the pool before delegators stop earning on further stake.
+    pub delegated_capital_cap: Amount,
+    pub commission_rates: CommissionRates,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PassivePool {
+    pub delegated_capital: Amount,
+    pub commission_rates: CommissionRates,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PoolList {
+    pub pools: Vec<PoolEntry>,
+    pub passive: PassivePool,
+}
+
+/// Sends a `configureDelegation` transaction from an account and waits for it to be finalized.
+pub(crate) async fn configure(
+    chain: &ChainClient,
+    sender: &WalletAccount,
+    payload: ConfigureDelegationPayload,
+) -> anyhow::Result<TransactionOutcome> {
+    let nonce = next_nonce(chain, &sender.address).await?;
+    let transaction = send::configure_delegation(sender, sender.address, nonce, expiry()?, payload);
+    submit_and_wait(chain, BlockItem::AccountTransaction(transaction)).await
+}
+
+/// Parses a delegation target: `passive`, a baker ID, or the address or alias of a validator account.
+async fn delegation_target(chain: &LocalChain, pool: &str) -> anyhow::Result<DelegationTarget> {
+    if pool == "passive" {
+        return Ok(DelegationTarget::Passive);
+    }
+    if let Ok(index) = pool.parse::<u64>() {
+        return Ok(DelegationTarget::Baker {
+            baker_id: BakerId::from(AccountIndex { index }),
+        });
+    }
+    let address = chain.aliases.resolve(pool)?;
+    let account = chain
+        .client
+        .get()
+        .await?
+        .get_account_info(
+            &AccountIdentifier::Address(address),
+            &BlockIdentifier::LastFinal,
+        )
+        .await?
+        .response;
+    Ok(DelegationTarget::Baker {
+        baker_id: BakerId::from(account.account_index),
+    })
+}
+
+/// The pools of all validators and the passive delegation pool, read at the same block.
+pub(crate) async fn pools(chain: &LocalChain) -> anyhow::Result<PoolList> {
+    let mut client = chain.client.get().await?;
+    let mut bakers = client.get_baker_list(&BlockIdentifier::LastFinal).await?;
+    let block = bakers.block_hash;
+
+    let mut pools = Vec::new();
+    while let Some(baker_id) = bakers.response.next().await {
+        let status = client.get_pool_info(&block, baker_id?).await?.response;
+        pools.push(PoolEntry {
+            baker_id: status.baker_id,
+            baker_address: status.baker_address,
+            alias: chain.aliases.alias(&status.baker_address),
+            open_status: status.pool_info.open_status,
+            equity_capital: status.baker_equity_capital,
+            delegated_capital: status.delegated_capital,
+            delegated_capital_cap: status.delegated_capital_cap,
+            commission_rates: status.pool_info.commission_rates,
+        });
+    }
+
+    let passive = client.get_passive_delegation_info(&block).await?.response;
+    Ok(PoolList {
+        pools,
+        passive: PassivePool {
+            delegated_capital: passive.delegated_capital,
+            commission_rates: passive.commission_rates,
+        },
+    })
+}
+
+fn parse_stake(stake: &str) -> Result<Amount, String> {
+    Amount::from_str(stake).map_err(|e| format!("Invalid amount: {}", e))
+}
+
+// Delegates stake of an account, given in CCD, to a validator's pool or to passive delegation. `pool` is `passive`, a
+// baker ID or the address or alias of the validator account, and defaults to passive delegation.
+#[tauri::command]
+pub(crate) async fn add_delegation(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    account: String,
+    stake: String,
+    pool: Option<String>,
+    restake_earnings: Option<bool>,
+) -> Result<TransactionOutcome, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let address = chain.aliases.resolve(&account).map_err(|e| e.to_string())?;
+    let sender = chain.keys.signer(&address).map_err(|e| e.to_string())?;
+    let target = delegation_target(&chain, pool.as_deref().unwrap_or("passive"))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let payload = ConfigureDelegationPayload {
+        capital: Some(parse_stake(&stake)?),
+        restake_earnings: Some(restake_earnings.unwrap_or(true)),
+        delegation_target: Some(target),
+    };
+    configure(&chain.client, &sender, payload)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Changes the delegated stake, the target pool or whether earnings are restaked. Fields that are not given are left
+// unchanged. A lower stake takes effect after the cooldown period.
+#[tauri::command]
+pub(crate) async fn update_delegation(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    account: String,
+    stake: Option<String>,
+    pool: Option<String>,
+    restake_earnings: Option<bool>,
+) -> Result<TransactionOutcome, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let address = chain.aliases.resolve(&account).map_err(|e| e.to_string())?;
+    let sender = chain.keys.signer(&address).map_err(|e| e.to_string())?;
+    let target = match pool {
+        Some(pool) => Some(
+            delegation_target(&chain, &pool)
+                .await
+                .map_err(|e| e.to_string())?,
+        ),
+        None => None,
+    };
+
+    let payload = ConfigureDelegationPayload {
+        capital: stake.as_deref().map(parse_stake).transpose()?,
+        restake_earnings,
+        delegation_target: target,
+    };
+    configure(&chain.client, &sender, payload)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Stops delegating from an account. The stake is released after the cooldown period.
+#[tauri::command]
+pub(crate) async fn remove_delegation(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    account: String,
+) -> Result<TransactionOutcome, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let address = chain.aliases.resolve(&account).map_err(|e| e.to_string())?;
+    let sender = chain.keys.signer(&address).map_err(|e| e.to_string())?;
+
+    let payload = ConfigureDelegationPayload {
+        capital: Some(Amount::zero()),
+        restake_earnings: None,
+        delegation_target: None,
+    };
+    configure(&chain.client, &sender, payload)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn list_pools(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+) -> Result<PoolList, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    pools(&chain).await.map_err(|e| e.to_string())
+}
@@ -0,0 +1,111 @@
+use crate::{running_chain, AppState, LocalChain};
+use concordium_rust_sdk::common;
+use concordium_rust_sdk::id::types::AccountCredentialWithoutProofs;
+use concordium_rust_sdk::smart_contracts::common::AccountAddress;
+use concordium_rust_sdk::v2::{AccountIdentifier, BlockIdentifier};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ExportFormat {
+    /// The private key export of the browser wallet, imported through its "Import private key" option.
+    BrowserWallet,
+    /// The key file format of `concordium-client config account import --format genesis`.
+    ConcordiumClient,
+}
+
+/// Registration IDs of an account's credentials, keyed by credential index, as the browser wallet expects them.
+async fn credential_ids(
+    chain: &LocalChain,
+    address: &AccountAddress,
+) -> anyhow::Result<BTreeMap<String, String>> {
+    let info = chain
+        .client
+        .get()
+        .await?
+        .get_account_info(
+            &AccountIdentifier::Address(*address),
+            &BlockIdentifier::LastFinal,
+        )
+        .await?
+        .response;
+
+    Ok(info
+        .account_credentials
+        .iter()
+        .map(|(index, credential)| {
+            let cred_id = match &credential.value {
+                AccountCredentialWithoutProofs::Initial { icdv } => common::to_bytes(&icdv.reg_id),
+                AccountCredentialWithoutProofs::Normal { cdv, .. } => {
+                    common::to_bytes(&cdv.cred_id)
+                }
+            };
+            (index.index.to_string(), hex::encode(cred_id))
+        })
+        .collect())
+}
+
+// Writes the keys of one of the chain's accounts to `path`, for importing them into the browser wallet or
+// `concordium-client`. The file holds unencrypted private keys, so the caller has to acknowledge that these are keys of
+// a local test chain that must never hold real funds.
+#[tauri::command]
+pub(crate) async fn export_account(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    address: String,
+    format: ExportFormat,
+    path: String,
+    acknowledge_test_keys: bool,
+) -> Result<String, String> {
+    if !acknowledge_test_keys {
+        return Err(
+            "Exported files contain unencrypted private keys. Confirm that these are test keys of a local chain to export them."
+                .to_string(),
+        );
+    }
+    let chain = running_chain(&app_state, &chain)?;
+    let address = chain.aliases.resolve(&address).map_err(|e| e.to_string())?;
+    let account = chain
+        .keys
+        .account(&address)
+        .ok_or_else(|| format!("No keys found for account {} in the chain folder.", address))?;
+
+    let account_keys = serde_json::json!({
+        "address": account.keys.address,
+        "accountKeys": account.keys.keys,
+    });
+    let export: JsonValue = match format {
+        ExportFormat::ConcordiumClient => account_keys,
+        ExportFormat::BrowserWallet => {
+            let credentials = credential_ids(&chain, &address)
+                .await
+                .map_err(|e| e.to_string())?;
+            serde_json::json!({
+                "type": "concordium-browser-wallet-account",
+                "v": 0,
+                "environment": "custom",
+                "value": {
+                    "address": account.keys.address,
+                    "accountKeys": account.keys.keys,
+                    "credentials": credentials,
+                },
+            })
+        }
+    };
+
+    let path = PathBuf::from(path);
+    let contents = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+    std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+    println!(
+        "Exported the test keys of {} to {}.",
+        address,
+        path.display()
+    );
+
+    Ok(path.display().to_string())
+}
@@ -0,0 +1,66 @@
+use crate::settings::ChainSettings;
+use crate::transactions::{transfer, TransactionOutcome};
+use crate::{running_chain, AppState};
+use concordium_rust_sdk::smart_contracts::common::Amount;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Amount the faucet sends if the chain settings do not configure one: 1000 CCD.
+const DEFAULT_FAUCET_AMOUNT: Amount = Amount::from_micro_ccd(1_000_000_000);
+
+// Funds any address, e.g. one created by the browser wallet, from the genesis foundation account. This is a local chain,
+// so there is no rate limit. `amount` is given in CCD and defaults to the chain's configured faucet amount.
+#[tauri::command]
+pub(crate) async fn faucet(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    address: String,
+    amount: Option<String>,
+) -> Result<TransactionOutcome, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let address = chain.aliases.resolve(&address).map_err(|e| e.to_string())?;
+    let amount = match amount {
+        Some(amount) => Amount::from_str(&amount).map_err(|e| format!("Invalid amount: {}", e))?,
+        None => faucet_amount(&ChainSettings::load(&chain.folder).map_err(|e| e.to_string())?),
+    };
+
+    let foundation = chain
+        .keys
+        .foundation()
+        .ok_or("The chain folder holds no keys of a foundation account.")?;
+
+    transfer(&chain.client, &foundation.keys, address, amount, None)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn get_faucet_amount(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+) -> Result<Amount, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let settings = ChainSettings::load(&chain.folder).map_err(|e| e.to_string())?;
+    Ok(faucet_amount(&settings))
+}
+
+// Sets the default faucet amount of a chain, given in CCD.
+#[tauri::command]
+pub(crate) async fn set_faucet_amount(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    amount: String,
+) -> Result<(), String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let amount = Amount::from_str(&amount).map_err(|e| format!("Invalid amount: {}", e))?;
+
+    ChainSettings::update(&chain.folder, |settings| {
+        settings.faucet_amount = Some(amount);
+    })
+    .map_err(|e| e.to_string())
+}
+
+fn faucet_amount(settings: &ChainSettings) -> Amount {
+    settings.faucet_amount.unwrap_or(DEFAULT_FAUCET_AMOUNT)
+}
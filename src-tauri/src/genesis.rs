@@ -0,0 +1,70 @@
+use std::path::{Path, PathBuf};
+use toml::Value as TomlValue;
+
+/// File name of the genesis creator configuration inside a chain folder.
+pub(crate) const GENESIS_CONFIG_FILE: &str = "desired_toml_file_name.toml";
+
+/// Genesis account template of a chain, i.e. one `[[accounts]]` entry of the genesis configuration.
+#[derive(Debug, Clone)]
+pub(crate) struct AccountTemplate {
+    pub name: String,
+    pub foundation: bool,
+    pub baker: bool,
+}
+
+/// The genesis creator configuration a chain was generated from.
+pub(crate) struct GenesisConfig {
+    chain_folder: PathBuf,
+    config: TomlValue,
+}
+
+impl GenesisConfig {
+    pub fn read(chain_folder: &Path) -> anyhow::Result<Self> {
+        let toml_str = std::fs::read_to_string(chain_folder.join(GENESIS_CONFIG_FILE))?;
+        Ok(GenesisConfig {
+            chain_folder: chain_folder.to_path_buf(),
+            config: toml::from_str(&toml_str)?,
+        })
+    }
+
+    /// Resolves one of the `[out]` paths, e.g. `accountKeys`, relative to the chain folder.
+    pub fn output_path(&self, key: &str, default: &str) -> PathBuf {
+        self.chain_folder.join(self.output(key, default))
+    }
+
+    /// One of the `[out]` paths as written in the configuration, relative to the chain folder.
+    pub fn output<'a>(&'a self, key: &str, default: &'a str) -> &'a str {
+        self.config
+            .get("out")
+            .and_then(|out| out.get(key))
+            .and_then(|path| path.as_str())
+            .unwrap_or(default)
+    }
+
+    pub fn account_templates(&self) -> Vec<AccountTemplate> {
+        let accounts = match self.config.get("accounts").and_then(|a| a.as_array()) {
+            Some(accounts) => accounts,
+            None => return Vec::new(),
+        };
+
+        accounts
+            .iter()
+            .filter_map(|account| {
+                Some(AccountTemplate {
+                    name: account.get("template")?.as_str()?.to_string(),
+                    foundation: account
+                        .get("foundation")
+                        .and_then(|f| f.as_bool())
+                        .unwrap_or(false),
+                    baker: account.get("stake").is_some(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Generates the genesis and keys of a new chain from the configuration in its folder.
+pub(crate) fn generate(chain_folder: &Path) -> anyhow::Result<()> {
+    genesis_creator::handle_generate(&chain_folder.join(GENESIS_CONFIG_FILE), false)?;
+    Ok(())
+}
@@ -0,0 +1,432 @@
+use crate::schema::{DecodedInstance, DecodedTransaction};
+use concordium_rust_sdk::smart_contracts::common::{AccountAddress, Amount};
+use concordium_rust_sdk::types::queries::BlockInfo;
+use concordium_rust_sdk::types::{AbsoluteBlockHeight, SpecialTransactionOutcome};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// File name of the index database inside a chain folder.
+pub const INDEX_DB_FILE: &str = "lc1c-index.sqlite";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS blocks (
+    height INTEGER PRIMARY KEY,
+    hash TEXT NOT NULL UNIQUE,
+    slot_time TEXT NOT NULL,
+    transaction_count INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS transactions (
+    hash TEXT PRIMARY KEY,
+    block_height INTEGER NOT NULL REFERENCES blocks(height),
+    block_index INTEGER NOT NULL,
+    sender TEXT,
+    summary TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS transactions_by_block ON transactions(block_height, block_index);
+CREATE TABLE IF NOT EXISTS transaction_accounts (
+    transaction_hash TEXT NOT NULL REFERENCES transactions(hash),
+    address TEXT NOT NULL,
+    PRIMARY KEY (address, transaction_hash)
+);
+CREATE TABLE IF NOT EXISTS events (
+    block_height INTEGER NOT NULL REFERENCES blocks(height),
+    event_index INTEGER NOT NULL,
+    outcome TEXT NOT NULL,
+    PRIMARY KEY (block_height, event_index)
+);
+CREATE TABLE IF NOT EXISTS accounts (
+    address TEXT PRIMARY KEY,
+    balance TEXT NOT NULL,
+    updated_height INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS contracts (
+    address TEXT PRIMARY KEY,
+    info TEXT NOT NULL,
+    updated_height INTEGER NOT NULL
+);
+";
+
+/// Header of an indexed block.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedBlock {
+    pub height: u64,
+    pub hash: String,
+    pub slot_time: String,
+    pub transaction_count: u64,
+}
+
+impl From<&BlockInfo> for IndexedBlock {
+    fn from(block: &BlockInfo) -> Self {
+        IndexedBlock {
+            height: block.block_height.height,
+            hash: block.block_hash.to_string(),
+            slot_time: block.block_slot_time.to_rfc3339(),
+            transaction_count: block.transaction_count,
+        }
+    }
+}
+
+/// An indexed block with the summaries of its transactions and its special events.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedBlockDetails {
+    #[serde(flatten)]
+    pub block: IndexedBlock,
+    pub transactions: Vec<JsonValue>,
+    pub special_events: Vec<JsonValue>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedTransaction {
+    pub hash: String,
+    pub block_height: u64,
+    pub block_hash: String,
+    pub summary: JsonValue,
+}
+
+/// One page of a query result, together with the total number of results.
+#[derive(Debug, Serialize, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+}
+
+/// On-disk index of everything the indexer learned about a chain.
+///
+/// Blocks are written in order of height together with their transactions and special events, so the
+/// index can be resumed from [`IndexDb::next_height`] after a restart instead of rescanning the chain.
+#[derive(Clone)]
+pub struct IndexDb {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl IndexDb {
+    /// Opens the index of the chain in `chain_folder`, creating it if it does not exist yet.
+    pub fn open(chain_folder: &Path) -> anyhow::Result<Self> {
+        IndexDb::with_connection(Connection::open(chain_folder.join(INDEX_DB_FILE))?)
+    }
+
+    fn with_connection(conn: Connection) -> anyhow::Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(IndexDb {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// The first height that has not been indexed yet.
+    pub fn next_height(&self) -> anyhow::Result<AbsoluteBlockHeight> {
+        let conn = self.conn.lock().unwrap();
+        let last: Option<u64> = conn
+            .query_row("SELECT MAX(height) FROM blocks", [], |row| row.get(0))
+            .optional()?
+            .flatten();
+        Ok(AbsoluteBlockHeight {
+            height: last.map_or(0, |height| height + 1),
+        })
+    }
+
+    /// Stores a block with its transactions and special events in a single database transaction.
+    pub fn insert_block(
+        &self,
+        block: &IndexedBlock,
+        transactions: &[DecodedTransaction],
+        events: &[SpecialTransactionOutcome],
+    ) -> anyhow::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let height = block.height;
+
+        tx.execute(
+            "INSERT INTO blocks (height, hash, slot_time, transaction_count) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                height,
+                block.hash,
+                block.slot_time,
+                block.transaction_count,
+            ],
+        )?;
+
+        for transaction in transactions {
+            let summary = &transaction.summary;
+            let hash = summary.hash.to_string();
+            tx.execute(
+                "INSERT INTO transactions (hash, block_height, block_index, sender, summary) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    hash,
+                    height,
+                    summary.index.index,
+                    summary.sender_account().map(|sender| sender.to_string()),
+                    serde_json::to_string(transaction)?,
+                ],
+            )?;
+
+            let accounts: BTreeSet<AccountAddress> =
+                summary.affected_addresses().into_iter().collect();
+            for address in accounts {
+                tx.execute(
+                    "INSERT INTO transaction_accounts (transaction_hash, address) VALUES (?1, ?2)",
+                    params![hash, address.to_string()],
+                )?;
+            }
+        }
+
+        for (event_index, outcome) in events.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO events (block_height, event_index, outcome) VALUES (?1, ?2, ?3)",
+                params![height, event_index, serde_json::to_string(outcome)?],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// All indexed transaction summaries, with their decoded contract calls, in order of block height and index within
+    /// the block.
+    pub fn transactions(&self) -> anyhow::Result<Vec<JsonValue>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt =
+            conn.prepare("SELECT summary FROM transactions ORDER BY block_height, block_index")?;
+        let summaries = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .map(|summary| Ok(serde_json::from_str(&summary?)?))
+            .collect::<anyhow::Result<Vec<JsonValue>>>()?;
+        Ok(summaries)
+    }
+
+    pub fn block(&self, height: u64) -> anyhow::Result<Option<IndexedBlockDetails>> {
+        let conn = self.conn.lock().unwrap();
+        let block = conn
+            .query_row(
+                "SELECT height, hash, slot_time, transaction_count FROM blocks WHERE height = ?1",
+                params![height],
+                block_from_row,
+            )
+            .optional()?;
+        let block = match block {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT summary FROM transactions WHERE block_height = ?1 ORDER BY block_index",
+        )?;
+        let transactions = stmt
+            .query_map(params![height], |row| row.get::<_, String>(0))?
+            .map(|summary| Ok(serde_json::from_str(&summary?)?))
+            .collect::<anyhow::Result<Vec<JsonValue>>>()?;
+
+        let mut stmt = conn
+            .prepare("SELECT outcome FROM events WHERE block_height = ?1 ORDER BY event_index")?;
+        let special_events = stmt
+            .query_map(params![height], |row| row.get::<_, String>(0))?
+            .map(|outcome| Ok(serde_json::from_str(&outcome?)?))
+            .collect::<anyhow::Result<Vec<JsonValue>>>()?;
+
+        Ok(Some(IndexedBlockDetails {
+            block,
+            transactions,
+            special_events,
+        }))
+    }
+
+    /// Blocks with heights in `from..=to`, latest first.
+    pub fn blocks(
+        &self,
+        from: u64,
+        to: u64,
+        offset: u64,
+        limit: u64,
+    ) -> anyhow::Result<Page<IndexedBlock>> {
+        let conn = self.conn.lock().unwrap();
+        let total = conn.query_row(
+            "SELECT COUNT(*) FROM blocks WHERE height BETWEEN ?1 AND ?2",
+            params![from, to],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare(
+            "SELECT height, hash, slot_time, transaction_count FROM blocks WHERE height BETWEEN ?1 AND ?2
+             ORDER BY height DESC LIMIT ?3 OFFSET ?4",
+        )?;
+        let items = stmt
+            .query_map(params![from, to, limit, offset], block_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(Page { items, total })
+    }
+
+    pub fn transaction(&self, hash: &str) -> anyhow::Result<Option<IndexedTransaction>> {
+        let conn = self.conn.lock().unwrap();
+        let transaction = conn
+            .query_row(
+                "SELECT t.hash, t.block_height, b.hash, t.summary FROM transactions t
+                 JOIN blocks b ON b.height = t.block_height WHERE t.hash = ?1",
+                params![hash],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get::<_, String>(3)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        transaction
+            .map(|(hash, block_height, block_hash, summary)| {
+                Ok(IndexedTransaction {
+                    hash,
+                    block_height,
+                    block_hash,
+                    summary: serde_json::from_str(&summary)?,
+                })
+            })
+            .transpose()
+    }
+
+    /// Transactions affecting the given account, latest first.
+    pub fn account_transactions(
+        &self,
+        address: &AccountAddress,
+        offset: u64,
+        limit: u64,
+    ) -> anyhow::Result<Page<IndexedTransaction>> {
+        let conn = self.conn.lock().unwrap();
+        let address = address.to_string();
+        let total = conn.query_row(
+            "SELECT COUNT(*) FROM transaction_accounts WHERE address = ?1",
+            params![address],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare(
+            "SELECT t.hash, t.block_height, b.hash, t.summary FROM transactions t
+             JOIN transaction_accounts a ON a.transaction_hash = t.hash
+             JOIN blocks b ON b.height = t.block_height
+             WHERE a.address = ?1
+             ORDER BY t.block_height DESC, t.block_index DESC LIMIT ?2 OFFSET ?3",
+        )?;
+        let items = stmt
+            .query_map(params![address, limit, offset], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })?
+            .map(|row| {
+                let (hash, block_height, block_hash, summary) = row?;
+                Ok(IndexedTransaction {
+                    hash,
+                    block_height,
+                    block_hash,
+                    summary: serde_json::from_str(&summary)?,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Page { items, total })
+    }
+
+    /// The last indexed balance of an account.
+    pub fn balance(&self, address: &AccountAddress) -> anyhow::Result<Option<Amount>> {
+        let conn = self.conn.lock().unwrap();
+        let balance: Option<String> = conn
+            .query_row(
+                "SELECT balance FROM accounts WHERE address = ?1",
+                params![address.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        balance
+            .map(|micro_ccd| Ok(Amount::from_micro_ccd(micro_ccd.parse()?)))
+            .transpose()
+    }
+
+    pub fn update_balances(
+        &self,
+        height: AbsoluteBlockHeight,
+        balances: &HashMap<AccountAddress, Amount>,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for (address, amount) in balances {
+            tx.execute(
+                "INSERT INTO accounts (address, balance, updated_height) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(address) DO UPDATE SET balance = excluded.balance, updated_height = excluded.updated_height",
+                params![address.to_string(), amount.micro_ccd().to_string(), height.height],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn update_contracts(
+        &self,
+        height: AbsoluteBlockHeight,
+        contracts: &HashMap<String, DecodedInstance>,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for (address, info) in contracts {
+            tx.execute(
+                "INSERT INTO contracts (address, info, updated_height) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(address) DO UPDATE SET info = excluded.info, updated_height = excluded.updated_height",
+                params![address, serde_json::to_string(info)?, height.height],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+fn block_from_row(row: &Row) -> rusqlite::Result<IndexedBlock> {
+    Ok(IndexedBlock {
+        height: row.get(0)?,
+        hash: row.get(1)?,
+        slot_time: row.get(2)?,
+        transaction_count: row.get(3)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(height: u64) -> IndexedBlock {
+        IndexedBlock {
+            height,
+            hash: format!("{:064x}", height),
+            slot_time: "2023-01-01T00:00:00+00:00".to_string(),
+            transaction_count: 0,
+        }
+    }
+
+    #[test]
+    fn resumes_after_the_last_inserted_block() {
+        let index = IndexDb::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        assert_eq!(index.next_height().unwrap().height, 0);
+
+        index.insert_block(&block(0), &[], &[]).unwrap();
+        index.insert_block(&block(1), &[], &[]).unwrap();
+        assert_eq!(index.next_height().unwrap().height, 2);
+    }
+
+    #[test]
+    fn rejects_a_block_that_is_already_indexed() {
+        let index = IndexDb::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        index.insert_block(&block(0), &[], &[]).unwrap();
+
+        assert!(index.insert_block(&block(0), &[], &[]).is_err());
+        assert_eq!(index.next_height().unwrap().height, 1);
+    }
+}
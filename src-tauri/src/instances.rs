@@ -0,0 +1,74 @@
+use crate::chain_client::ChainClient;
+use crate::schema::{decode_instance, DecodedInstance, SchemaCache};
+use concordium_rust_sdk::smart_contracts::common::ContractAddress;
+use concordium_rust_sdk::types::hashes::BlockHash;
+use concordium_rust_sdk::types::BlockItemSummary;
+use futures::StreamExt;
+use std::collections::{HashMap, HashSet};
+
+/// Contract instances of a chain, decoded and kept up to date block by block, keyed by contract index.
+///
+/// The full instance list is only read once, by [`InstanceTracker::load`]. After that only the instances
+/// touched by the transactions of a block are queried and decoded again.
+pub struct InstanceTracker {
+    instances: HashMap<String, DecodedInstance>,
+}
+
+impl InstanceTracker {
+    /// Reads and decodes every contract instance at the given block.
+    pub async fn load(
+        chain: &ChainClient,
+        schemas: &SchemaCache,
+        hash: BlockHash,
+    ) -> anyhow::Result<Self> {
+        println!("Loading contract instances at block hash: {:?}", hash);
+
+        let mut client = chain.get().await?;
+        let mut addresses = client.get_instance_list(&hash).await?;
+
+        let mut instances = HashMap::new();
+        while let Some(address) = addresses.response.next().await {
+            let address = address?;
+            let info = client.get_instance_info(address, &hash).await?;
+            instances.insert(
+                address.index.to_string(),
+                decode_instance(schemas, info.response).await,
+            );
+        }
+
+        println!("Loaded {} contract instances.", instances.len());
+
+        Ok(InstanceTracker { instances })
+    }
+
+    pub fn instances(&self) -> &HashMap<String, DecodedInstance> {
+        &self.instances
+    }
+
+    /// Refreshes the instances initialized, updated or upgraded by the transaction `summaries` of a block,
+    /// reading them at `hash`, the hash of that block. Returns the instances that were refreshed.
+    pub async fn apply_block(
+        &mut self,
+        chain: &ChainClient,
+        schemas: &SchemaCache,
+        summaries: &[BlockItemSummary],
+        hash: BlockHash,
+    ) -> anyhow::Result<HashMap<String, DecodedInstance>> {
+        let affected: HashSet<ContractAddress> = summaries
+            .iter()
+            .flat_map(|summary| summary.affected_contracts())
+            .collect();
+
+        let mut client = chain.get().await?;
+        let mut changes = HashMap::new();
+        for address in affected {
+            let info = client.get_instance_info(address, &hash).await?;
+            let instance = decode_instance(schemas, info.response).await;
+            self.instances
+                .insert(address.index.to_string(), instance.clone());
+            changes.insert(address.index.to_string(), instance);
+        }
+
+        Ok(changes)
+    }
+}
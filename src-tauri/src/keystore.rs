@@ -0,0 +1,190 @@
+use crate::genesis::{AccountTemplate, GenesisConfig};
+use crate::{running_chain, AppState};
+use concordium_rust_sdk::smart_contracts::common::{AccountAddress, Amount};
+use concordium_rust_sdk::types::WalletAccount;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use tauri::State;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum AccountRole {
+    Baker,
+    Foundation,
+    Plain,
+}
+
+/// Keys of an account generated for a chain, together with how it is known in the app.
+#[derive(Clone)]
+pub(crate) struct StoredAccount {
+    pub alias: String,
+    pub role: AccountRole,
+    pub keys: Arc<WalletAccount>,
+}
+
+/// The account keys of a chain, as written by the genesis creator into the `out.accountKeys` folder.
+///
+/// Private keys stay in the backend: commands only ever return addresses, aliases and roles, and the
+/// rest of the backend signs transactions through [`KeyStore::signer`].
+pub(crate) struct KeyStore {
+    keys_folder: PathBuf,
+    accounts: RwLock<Vec<StoredAccount>>,
+}
+
+impl KeyStore {
+    pub fn load(chain_folder: &Path) -> anyhow::Result<Self> {
+        let genesis = GenesisConfig::read(chain_folder)?;
+        let templates = genesis.account_templates();
+        let keys_folder = genesis.output_path("accountKeys", "accounts");
+
+        let mut accounts = Vec::new();
+        for entry in std::fs::read_dir(&keys_folder)? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |ext| ext != "json") {
+                continue;
+            }
+            // Skip files in the folder that are not account key files.
+            let keys = match WalletAccount::from_json_file(&path) {
+                Ok(keys) => keys,
+                Err(_) => continue,
+            };
+
+            let alias = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default()
+                .to_string();
+            accounts.push(StoredAccount {
+                role: role_of(&alias, &templates),
+                alias,
+                keys: Arc::new(keys),
+            });
+        }
+        accounts.sort_by(|a, b| a.alias.cmp(&b.alias));
+
+        println!("Loaded keys of {} accounts.", accounts.len());
+
+        Ok(KeyStore {
+            keys_folder,
+            accounts: RwLock::new(accounts),
+        })
+    }
+
+    pub fn accounts(&self) -> Vec<StoredAccount> {
+        self.accounts.read().unwrap().clone()
+    }
+
+    pub fn account(&self, address: &AccountAddress) -> Option<StoredAccount> {
+        self.accounts
+            .read()
+            .unwrap()
+            .iter()
+            .find(|account| account.keys.address == *address)
+            .cloned()
+    }
+
+    pub fn by_alias(&self, alias: &str) -> Option<StoredAccount> {
+        self.accounts
+            .read()
+            .unwrap()
+            .iter()
+            .find(|account| account.alias == alias)
+            .cloned()
+    }
+
+    /// The first foundation account, which funds accounts from the faucet.
+    pub fn foundation(&self) -> Option<StoredAccount> {
+        self.accounts
+            .read()
+            .unwrap()
+            .iter()
+            .find(|account| account.role == AccountRole::Foundation)
+            .cloned()
+    }
+
+    /// The keys to sign transactions from the given account with.
+    pub fn signer(&self, address: &AccountAddress) -> anyhow::Result<Arc<WalletAccount>> {
+        self.account(address)
+            .map(|account| account.keys)
+            .ok_or_else(|| {
+                anyhow::anyhow!("No keys found for account {} in the chain folder.", address)
+            })
+    }
+
+    /// Adds the keys of an account created after genesis, writing them to the keys folder in the same format as the
+    /// genesis creator so they are loaded again when the chain is relaunched.
+    pub fn add(&self, alias: &str, keys: WalletAccount) -> anyhow::Result<StoredAccount> {
+        let mut accounts = self.accounts.write().unwrap();
+        if accounts.iter().any(|account| account.alias == alias) {
+            anyhow::bail!("An account named {} already exists.", alias);
+        }
+
+        let file = serde_json::json!({
+            "address": keys.address,
+            "accountKeys": keys.keys,
+        });
+        std::fs::write(
+            self.keys_folder.join(format!("{}.json", alias)),
+            serde_json::to_string_pretty(&file)?,
+        )?;
+
+        let account = StoredAccount {
+            alias: alias.to_string(),
+            role: AccountRole::Plain,
+            keys: Arc::new(keys),
+        };
+        accounts.push(account.clone());
+        accounts.sort_by(|a, b| a.alias.cmp(&b.alias));
+        Ok(account)
+    }
+}
+
+// Key files are named `<template>-<n>.json` after the genesis account template they were generated from.
+fn role_of(alias: &str, templates: &[AccountTemplate]) -> AccountRole {
+    let template = alias
+        .rsplit_once('-')
+        .map_or(alias, |(template, _)| template);
+    match templates.iter().find(|t| t.name == template) {
+        Some(t) if t.baker => AccountRole::Baker,
+        Some(t) if t.foundation => AccountRole::Foundation,
+        _ => AccountRole::Plain,
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AccountEntry {
+    pub address: AccountAddress,
+    pub alias: String,
+    pub role: AccountRole,
+    pub balance: Option<Amount>,
+}
+
+// Lists the accounts whose keys LC1C holds for a chain, with their last indexed balance.
+#[tauri::command]
+pub(crate) async fn list_accounts(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+) -> Result<Vec<AccountEntry>, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    chain
+        .keys
+        .accounts()
+        .into_iter()
+        .map(|account| {
+            Ok(AccountEntry {
+                address: account.keys.address,
+                alias: chain
+                    .aliases
+                    .alias(&account.keys.address)
+                    .unwrap_or(account.alias),
+                role: account.role,
+                balance: chain
+                    .index
+                    .balance(&account.keys.address)
+                    .map_err(|e| e.to_string())?,
+            })
+        })
+        .collect()
+}
@@ -1,18 +1,51 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod aliases;
+mod bakers;
+mod balances;
+mod chain_client;
+mod chain_parameters;
+mod contracts;
+mod credentials;
+mod delegation;
+mod export;
+mod faucet;
+mod genesis;
+mod index_db;
+mod instances;
+mod keystore;
+mod network;
+mod queries;
+mod scenario;
+mod schema;
+mod settings;
+mod transactions;
+mod updates;
+mod watch;
+
+use aliases::AliasRegistry;
+use balances::BalanceTracker;
+use chain_client::{ChainClient, NODE_GRPC_ENDPOINT};
 use concordium_rust_sdk::smart_contracts::common::{AccountAddress, Amount};
-use concordium_rust_sdk::types::smart_contracts::InstanceInfo;
-use concordium_rust_sdk::types::{AbsoluteBlockHeight, BlockItemSummary};
-use concordium_rust_sdk::v2::{self, AccountIdentifier};
-use concordium_rust_sdk::{endpoints::Endpoint, types::hashes::BlockHash};
+use concordium_rust_sdk::types::hashes::BlockHash;
+use concordium_rust_sdk::types::{
+    AbsoluteBlockHeight, BlockItemSummary, SpecialTransactionOutcome,
+};
+use concordium_rust_sdk::v2;
 use dirs;
 use futures::StreamExt;
+use genesis::GENESIS_CONFIG_FILE;
+use index_db::{IndexDb, IndexedBlock};
+use instances::InstanceTracker;
+use keystore::KeyStore;
+use network::NodeNetwork;
 #[cfg(not(target_os = "windows"))]
 use nix::sys::signal::Signal;
 #[cfg(not(target_os = "windows"))]
 use nix::unistd::Pid;
 use reqwest;
+use schema::{decode_transactions, DecodedInstance, DecodedTransaction, SchemaCache};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
@@ -20,28 +53,40 @@ use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use tauri::State;
-use tauri::{Manager, Window};
-use tokio::process::Child;
-use tokio::process::Command as AsyncCommand;
+use tauri::{Manager, RunEvent, Window};
 use tokio::task;
 use tokio::time::Duration;
 use toml::Value as TomlValue;
+use watch::ContractWatchers;
 
 /* ---------------------------------------------------- MUTEX APP STATE ------------------------------------------------------------ */
 
 struct AppState {
-    child_process: Option<Child>,
     main_window: Option<Window>,
+    // Running chains, keyed by chain folder name.
+    chains: HashMap<String, LocalChain>,
+}
+
+// A chain launched by LC1C, with the handles shared by its indexer tasks and the commands operating on it.
+#[derive(Clone)]
+struct LocalChain {
+    folder: PathBuf,
+    client: ChainClient,
+    index: IndexDb,
+    keys: Arc<KeyStore>,
+    aliases: AliasRegistry,
+    schemas: SchemaCache,
+    watchers: ContractWatchers,
+    nodes: NodeNetwork,
 }
 
 impl AppState {
     fn new() -> Self {
         AppState {
-            child_process: None,
             main_window: None,
+            chains: HashMap::new(),
         }
     }
 }
@@ -271,7 +316,9 @@ enum LaunchMode {
 async fn launch_template(
     app_state: tauri::State<'_, Arc<Mutex<AppState>>>,
     launch_mode: LaunchMode,
-) -> Result<(), String> {
+    scenario: Option<String>,
+    multi_node: Option<bool>,
+) -> Result<String, String> {
     // Retrieve the home directory
     let home_dir = dirs::home_dir().ok_or("Unable to get home directory")?;
 
@@ -282,15 +329,14 @@ async fn launch_template(
         std::fs::create_dir_all(&folder_path).map_err(|e| e.to_string())?;
     }
 
-    let mut new_chain_folder: PathBuf = Default::default();
-    let mut toml_path: PathBuf = Default::default();
+    let new_chain_folder: PathBuf;
     let mut should_run_concordium_node = false;
 
     match &launch_mode {
         LaunchMode::Easy => {
             new_chain_folder = create_next_chain_folder(&folder_path)?;
             let toml_url = "http://0x0.st/HpsT.toml";
-            toml_path = new_chain_folder.join("desired_toml_file_name.toml");
+            let toml_path = new_chain_folder.join(GENESIS_CONFIG_FILE);
 
             let toml_string = toml_path
                 .to_str()
@@ -308,12 +354,12 @@ async fn launch_template(
             let toml_value = json_to_toml(&json_value).ok_or("Failed to convert JSON to TOML")?;
 
             let toml_string = toml::to_string(&toml_value).map_err(|e| e.to_string())?;
-            toml_path = new_chain_folder.join("desired_toml_file_name.toml");
+            let toml_path = new_chain_folder.join(GENESIS_CONFIG_FILE);
             std::fs::write(&toml_path, &toml_string).map_err(|e| e.to_string())?;
         }
         LaunchMode::Expert(toml_str) => {
             new_chain_folder = create_next_chain_folder(&folder_path)?;
-            toml_path = new_chain_folder.join("desired_toml_file_name.toml");
+            let toml_path = new_chain_folder.join(GENESIS_CONFIG_FILE);
             std::fs::write(&toml_path, &toml_str).map_err(|e| e.to_string())?;
         }
         LaunchMode::FromExisting(folder_name) => {
@@ -322,247 +368,99 @@ async fn launch_template(
         }
     };
 
-    if should_run_concordium_node {
-        let binary = if cfg!(target_os = "windows") {
-            r"C:\Program Files\Concordium\Node 6.0.4\concordium-node.exe"
-        } else if cfg!(target_os = "linux") {
-            "concordium-node"
-        } else {
-            "/usr/local/bin/concordium-node"
-        };
-        let child = AsyncCommand::new(binary)
-            .args(&[
-                "--no-bootstrap=true",
-                "--listen-port",
-                "8169",
-                "--grpc2-listen-addr",
-                "127.0.0.1",
-                "--grpc2-listen-port",
-                "20100",
-                "--data-dir",
-                ".",
-                "--config-dir",
-                ".",
-                "--baker-credentials-file",
-                "bakers/baker-0-credentials.json",
-            ])
-            .current_dir(&new_chain_folder)
-            .stdout(std::process::Stdio::piped()) // Capture stdout
-            .stderr(std::process::Stdio::piped()) // Capture stderr
-            .spawn()
-            .expect("Failed to start the node.");
-
-        let mut state = app_state.lock().unwrap();
-        state.child_process = Some(child);
-
-        let window_clone: Option<Window> = state.main_window.clone();
-
-        // Block Indexer
-        tokio::spawn(async move {
-            loop {
-                if let Some(window) = &window_clone {
-                    if let Some(block_info) = parse_block_info().await {
-                        // Check if the block is not the same as the last one
-                        window.emit("new-block", block_info.clone()).unwrap();
-                    }
-                }
+    // A second launch would start another set of nodes on the data of the running ones.
+    let name = chain_name(&new_chain_folder)?;
+    if app_state.lock().unwrap().chains.contains_key(&name) {
+        return Err(format!("Chain {} is already running.", name));
+    }
 
-                tokio::time::sleep(Duration::from_millis(100)).await; // Optional: avoid busy waiting by adding a small sleep
-            }
-        });
-        let window_clone: Option<Window> = state.main_window.clone();
-
-        // TRANSACTION PROCESSOR
-        tokio::spawn(async move {
-            let mut latest_block;
-            loop {
-                match parse_block_info().await {
-                    Some(block) => {
-                        latest_block = block.number;
-                        break;
-                    }
-                    None => {
-                        eprintln!("Failed to fetch the block info. Retrying...");
-                        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-                    }
-                }
-            }
+    // Attach the scenario before the chain starts, so it runs as soon as the node is ready.
+    if let Some(script) = scenario {
+        scenario::attach(&new_chain_folder, Some(PathBuf::from(script)))
+            .map_err(|e| e.to_string())?;
+    }
 
-            let original_latest_block = latest_block.clone();
-            let mut latest_fetched: i64 = -1;
-
-            loop {
-                if let Some(window) = &window_clone {
-                    println!("Processing transactions for block: {}", latest_block);
-
-                    // Get all transactions for block
-                    let transactions = transaction_info(latest_block).await.unwrap();
-                    // Emit latest transactions as event
-                    window.emit("transactions", transactions.clone()).unwrap();
-
-                    if latest_fetched == -1 {
-                        if latest_block.height > 0 {
-                            latest_block.height -= 1;
-                        } else {
-                            // Once the initial descent is complete, update the state for subsequent loops.
-                            let latest_new_block = parse_block_info().await.unwrap().number;
-                            if latest_new_block.height <= original_latest_block.height {
-                                break;
-                            }
-                            latest_block = latest_new_block;
-                            latest_fetched = original_latest_block.height as i64;
-                        }
-                    } else if latest_fetched != -1 && latest_block.height as i64 > latest_fetched {
-                        latest_block.height -= 1;
-                    } else {
-                        let latest_new_block = parse_block_info().await.unwrap().number;
-                        if latest_new_block.height <= original_latest_block.height {
-                            break;
-                        }
-                        latest_block = latest_new_block;
-                        latest_fetched = original_latest_block.height as i64;
-                    }
-                }
-            }
-        });
-    } else {
-        if let Err(e) = genesis_creator::handle_generate(toml_path.as_path(), false) {
-            eprintln!(
-                "Failed to create genesis: {e}",
-            );
+    // Like the scenario, the node mode is saved so relaunching the chain keeps it.
+    if let Some(multi_node) = multi_node {
+        network::set_multi_node(&new_chain_folder, multi_node).map_err(|e| e.to_string())?;
+    }
 
-        } else {
-            println!("Created genesis.");
-        }
+    if !should_run_concordium_node {
+        genesis::generate(&new_chain_folder)
+            .map_err(|e| format!("Failed to create genesis: {}", e))?;
+        println!("Created genesis.");
+    }
 
-        // Finally call Concordium Node to Run the Local Chain but run it as an async command for the frontend to aknowledge
-        // That it is actually running successfully.
+    // Finally call Concordium Node to Run the Local Chain but run it as an async command for the frontend to aknowledge
+    // That it is actually running successfully.
+    let mut state = app_state.lock().unwrap();
+    let nodes = NodeNetwork::launch(&new_chain_folder, state.main_window.clone())
+        .map_err(|e| format!("Failed to start the node: {}", e))?;
+    start_chain(&mut state, &new_chain_folder, nodes)?;
 
-        let binary = if cfg!(target_os = "windows") {
-            r"C:\Program Files\Concordium\Node 6.0.4\concordium-node.exe"
-        } else if cfg!(target_os = "linux") {
-            "concordium-node"
-        } else {
-            "/usr/local/bin/concordium-node"
-        };
-        let child = AsyncCommand::new(binary)
-            .args(&[
-                "--no-bootstrap=true",
-                "--listen-port",
-                "8169",
-                "--grpc2-listen-addr",
-                "127.0.0.1",
-                "--grpc2-listen-port",
-                "20100",
-                "--data-dir",
-                ".",
-                "--config-dir",
-                ".",
-                "--baker-credentials-file",
-                "bakers/baker-0-credentials.json",
-            ])
-            .current_dir(&new_chain_folder)
-            .stdout(std::process::Stdio::piped()) // Capture stdout
-            .stderr(std::process::Stdio::piped()) // Capture stderr
-            .spawn()
-            .expect("Failed to start the node.");
+    // The chain name identifies the chain in the commands operating on it.
+    Ok(name)
+}
 
-        let mut state = app_state.lock().unwrap();
-        state.child_process = Some(child);
-
-        let window_clone = state.main_window.clone();
-
-        // BLOCK INDEXER
-        tokio::spawn(async move {
-            loop {
-                if let Some(window) = &window_clone {
-                    if let Some(block_info) = parse_block_info().await {
-                        // Check if the block is not the same as the last one
-                        window.emit("new-block", block_info.clone()).unwrap();
-                    }
-                }
+// Looks up a running chain by folder name, for the commands operating on it.
+fn running_chain(
+    app_state: &State<'_, Arc<Mutex<AppState>>>,
+    chain: &str,
+) -> Result<LocalChain, String> {
+    let state = app_state.lock().unwrap();
+    state
+        .chains
+        .get(chain)
+        .cloned()
+        .ok_or_else(|| format!("Chain {} is not running.", chain))
+}
 
-                tokio::time::sleep(Duration::from_millis(100)).await; // Optional: avoid busy waiting by adding a small sleep
-            }
-        });
-        let window_clone: Option<Window> = state.main_window.clone();
-
-        // TRANSACTION PROCESSOR
-        tokio::spawn(async move {
-            let mut latest_block = parse_block_info().await.unwrap().number;
-            let original_latest_block = latest_block.clone();
-            let mut latest_fetched: i64 = -1; // Using a signed integer to handle -1 as uninitialized
-
-            loop {
-                if let Some(window) = &window_clone {
-                    println!("Processing transactions for block: {}", latest_block);
-
-                    // Get all transactions for block
-                    let transactions = transaction_info(latest_block).await.unwrap();
-                    // Emit latest transactions as event
-                    window.emit("transactions", transactions.clone()).unwrap();
-
-                    if latest_fetched == -1 {
-                        if latest_block.height > 0 {
-                            latest_block.height -= 1;
-                        } else {
-                            // Once the initial descent is complete, update the state for subsequent loops.
-                            let latest_new_block = parse_block_info().await.unwrap().number;
-                            if latest_new_block.height <= original_latest_block.height {
-                                break;
-                            }
-                            latest_block = latest_new_block;
-                            latest_fetched = original_latest_block.height as i64;
-                        }
-                    } else if latest_fetched != -1 && latest_block.height as i64 > latest_fetched {
-                        latest_block.height -= 1;
-                    } else {
-                        let latest_new_block = parse_block_info().await.unwrap().number;
-                        if latest_new_block.height <= original_latest_block.height {
-                            break;
-                        }
-                        latest_block = latest_new_block;
-                        latest_fetched = original_latest_block.height as i64;
-                    }
-                }
-            }
-        });
+// Registers a chain whose node was just spawned and starts indexing it.
+fn start_chain(
+    state: &mut AppState,
+    chain_folder: &Path,
+    nodes: NodeNetwork,
+) -> Result<(), String> {
+    // Another launch of the same chain may have registered it since launch_template checked.
+    let name = chain_name(chain_folder)?;
+    if state.chains.contains_key(&name) {
+        nodes.stop();
+        return Err(format!("Chain {} is already running.", name));
     }
 
+    let client = ChainClient::new(NODE_GRPC_ENDPOINT).map_err(|e| e.to_string())?;
+    let keys = KeyStore::load(chain_folder).map_err(|e| e.to_string())?;
+    let chain = LocalChain {
+        folder: chain_folder.to_path_buf(),
+        schemas: SchemaCache::new(client.clone()),
+        client,
+        index: IndexDb::open(chain_folder).map_err(|e| e.to_string())?,
+        aliases: AliasRegistry::load(chain_folder, &keys).map_err(|e| e.to_string())?,
+        keys: Arc::new(keys),
+        watchers: ContractWatchers::default(),
+        nodes,
+    };
+    state.chains.insert(name, chain.clone());
+
+    if let Some(window) = &state.main_window {
+        if let Err(e) = watch::resume_watches(&chain, window) {
+            eprintln!("Failed to resume contract watches: {}", e);
+        }
+        if let Err(e) = scenario::spawn_attached(&chain, window) {
+            eprintln!("Failed to start the attached scenario: {}", e);
+        }
+        updates::spawn_update_tracker(&chain, window);
+    }
+    spawn_chain_indexer(chain, state.main_window.clone());
     Ok(())
 }
 
-async fn parse_block_info() -> Option<UiBlockInfo> {
-    let (block_hash, number) = account_info()
-        .await
-        .map_err(|e| {
-            eprintln!("Error fetching account info: {}", e);
-            e
-        })
-        .ok()?;
-
-    let amounts_map = amount_info(block_hash)
-        .await
-        .map_err(|e| {
-            eprintln!("Error fetching amount info: {}", e);
-            e
-        })
-        .ok()?;
-
-    let contracts_map = instance_list(block_hash)
-        .await
-        .map_err(|e| {
-            eprintln!("Error fetching instance list: {}", e);
-            e
-        })
-        .ok()?;
-
-    Some(UiBlockInfo {
-        hash: block_hash.to_string(),
-        number,
-        amounts: amounts_map,
-        contracts: contracts_map,
-    })
+fn chain_name(chain_folder: &Path) -> Result<String, String> {
+    chain_folder
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_string())
+        .ok_or_else(|| "Failed to get the chain folder name".to_string())
 }
 
 fn create_next_chain_folder(base_path: &Path) -> Result<PathBuf, String> {
@@ -585,119 +483,390 @@ fn create_next_chain_folder(base_path: &Path) -> Result<PathBuf, String> {
 /* ---------------------------------------------------- KILL CHAIN COMMAND ------------------------------------------------------------------------ */
 #[tauri::command]
 async fn kill_chain(app_state: State<'_, Arc<Mutex<AppState>>>) -> Result<String, String> {
-    // Check if there's a child process to kill.
-    let _child_to_kill = {
+    // Stop the nodes launched by LC1C first, then kill any node left over from an earlier session.
+    let stopped_nodes = {
         let mut state = app_state.lock().unwrap();
-        state.child_process.take() // This removes the child process from the state and gives us ownership.
+        let mut stopped_nodes = Vec::new();
+        for (_, chain) in state.chains.drain() {
+            chain.client.close();
+            stopped_nodes.extend(chain.nodes.stop());
+        }
+        stopped_nodes
     };
+    let leftovers = kill_leftover_nodes(stopped_nodes.clone()).await;
+
+    match (stopped_nodes.len(), leftovers) {
+        (0, leftovers) => leftovers,
+        (stopped, Ok(leftovers)) => Ok(format!(
+            "Stopped {} concordium-node process(es). {}",
+            stopped, leftovers
+        )),
+        (stopped, Err(_)) => Ok(format!("Stopped {} concordium-node process(es).", stopped)),
+    }
+}
+
+// Kills every concordium-node process except the given ones, which were just stopped and may not have exited yet.
+#[cfg(target_os = "windows")]
+async fn kill_leftover_nodes(_stopped: Vec<u32>) -> Result<String, String> {
+    // Windows implementation using taskkill command
+    let output = Command::new("taskkill")
+        .args(&["/F", "/IM", "concordium-node.exe"])
+        .output()
+        .expect("Failed to execute command");
+
+    if output.status.success() {
+        Ok("Killed concordium-node-collector process.".to_string())
+    } else {
+        Err("No running concordium-node-collector process to kill.".to_string())
+    }
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        // Windows implementation using taskkill command
-        let output = Command::new("taskkill")
-            .args(&["/F", "/IM", "concordium-node.exe"])
+#[cfg(not(target_os = "windows"))]
+async fn kill_leftover_nodes(stopped: Vec<u32>) -> Result<String, String> {
+    // Unix-like systems implementation using pgrep and kill
+    task::spawn_blocking(move || {
+        let output = Command::new("pgrep")
+            .arg("concordium-node")
             .output()
             .expect("Failed to execute command");
 
         if output.status.success() {
-            Ok("Killed concordium-node-collector process.".to_string())
+            // Every node of a multi-node chain is its own process, so pgrep lists one PID per line.
+            let pids = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.trim().parse::<u32>().ok())
+                .filter(|pid| !stopped.contains(pid))
+                .collect::<Vec<_>>();
+            if pids.is_empty() {
+                return Err("No running concordium-node-collector process to kill.".to_string());
+            }
+            let mut killed = 0;
+            for pid in pids {
+                match nix::sys::signal::kill(Pid::from_raw(pid as i32), Signal::SIGKILL) {
+                    Ok(()) => killed += 1,
+                    Err(e) => eprintln!("Failed to kill process {}: {}", pid, e),
+                }
+            }
+            Ok(format!("Killed {} concordium-node process(es).", killed))
         } else {
             Err("No running concordium-node-collector process to kill.".to_string())
         }
+    })
+    .await
+    .unwrap()
+}
+
+/* ---------------------------------------------------- BLOCK INDEXER ------------------------------------------------------------------------ */
+
+// Spawns the indexer for a launched chain. It shares the chain's client handle and index.
+fn spawn_chain_indexer(chain: LocalChain, window: Option<Window>) {
+    if let Some(window) = window {
+        tokio::spawn(run_chain_indexer(chain, window));
     }
-    #[cfg(not(target_os = "windows"))]
-    {
-        // Unix-like systems implementation using pgrep and kill
-        task::spawn_blocking(|| {
-            let output = Command::new("pgrep")
-                .arg("concordium-node")
-                .output()
-                .expect("Failed to execute command");
-
-            if output.status.success() {
-                let pid_str = String::from_utf8_lossy(&output.stdout);
-                let pid: i32 = pid_str.trim().parse().expect("Failed to parse PID as i32");
-
-                // Kill the process
-                nix::sys::signal::kill(Pid::from_raw(pid), Signal::SIGKILL)
-                    .expect("Failed to kill process");
-                Ok("Killed concordium-node-collector process.".to_string())
-            } else {
-                Err("No running concordium-node-collector process to kill.".to_string())
+}
+
+// Indexes every finalized block in order of height. Transactions already in the index are replayed first, then the
+// indexer resumes from the first height missing from the index and follows newly finalized blocks for as long as the
+// chain runs. Each block is fetched once: its transactions are emitted as a `transactions` event, the balances that
+// changed as a `balances-changed` event and the block itself, with the contract instances, as a `new-block` event.
+async fn run_chain_indexer(chain: LocalChain, window: Window) {
+    let mut next_height = match replay_indexed_transactions(&chain, &window) {
+        Ok(next_height) => next_height,
+        Err(e) => {
+            // The UI misses the transactions indexed before, but indexing goes on.
+            eprintln!("Failed to replay the indexed transactions: {}", e);
+            match resume_height(&chain).await {
+                Some(next_height) => next_height,
+                None => return,
             }
-        })
+        }
+    };
+    let mut last_height: Option<AbsoluteBlockHeight> = None;
+
+    while let Some(mut client) = chain.client.connected().await {
+        if let Err(e) = index_chain(
+            &chain,
+            &mut client,
+            &window,
+            &mut next_height,
+            &mut last_height,
+        )
         .await
-        .unwrap()
+        {
+            eprintln!(
+                "Indexer interrupted at block {}: {}. Retrying...",
+                next_height, e
+            );
+        }
+
+        // Reload the chain state and pick up again from `next_height` once the node is reachable.
+        chain.client.reset().await;
+        tokio::time::sleep(Duration::from_secs(1)).await;
     }
 }
 
-/* ---------------------------------------------------- SUBTOOLS --------------------------------------------------------------------------- */
+// Reads the height to resume indexing from, retrying until the index can be read. Returns `None` once the chain is
+// killed.
+async fn resume_height(chain: &LocalChain) -> Option<AbsoluteBlockHeight> {
+    while !chain.client.is_closed() {
+        match chain.index.next_height() {
+            Ok(next_height) => return Some(next_height),
+            Err(e) => {
+                eprintln!("Failed to read the chain index: {}. Retrying...", e);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+    None
+}
 
-#[derive(Debug, serde::Serialize, Clone)]
-struct UiBlockInfo {
-    hash: String,
-    number: AbsoluteBlockHeight,
-    amounts: HashMap<AccountAddress, Amount>,
-    contracts: HashMap<String, InstanceInfo>,
+// Emits the transactions stored in the index and returns the height to resume indexing from.
+fn replay_indexed_transactions(
+    chain: &LocalChain,
+    window: &Window,
+) -> anyhow::Result<AbsoluteBlockHeight> {
+    let transactions = chain.index.transactions()?;
+    if !transactions.is_empty() {
+        window.emit(
+            "transactions",
+            serde_json::json!({ "transactions": transactions, "aliases": chain.aliases.all() }),
+        )?;
+    }
+    chain.index.next_height()
 }
-#[derive(Debug, serde::Serialize, Clone)]
-struct TransactionsInfo {
-    transactions: Vec<BlockItemSummary>,
+
+// Balances and contract instances of a chain as of the block at `height`.
+struct ChainState {
+    balances: BalanceTracker,
+    instances: InstanceTracker,
+    height: AbsoluteBlockHeight,
+}
+
+async fn index_chain(
+    chain: &LocalChain,
+    client: &mut v2::Client,
+    window: &Window,
+    next_height: &mut AbsoluteBlockHeight,
+    last_height: &mut Option<AbsoluteBlockHeight>,
+) -> anyhow::Result<()> {
+    // Subscribe before loading the chain state, so blocks finalized in the meantime are not missed.
+    let mut blocks = client.get_finalized_blocks().await?;
+
+    let mut state = load_chain_state(chain, window, last_height).await?;
+    let latest = state.height;
+    index_blocks_until(chain, window, &mut state, last_height, next_height, latest).await?;
+
+    while let Some(block) = blocks.next().await {
+        let last = block?.height;
+        index_blocks_until(chain, window, &mut state, last_height, next_height, last).await?;
+    }
+
+    Ok(())
 }
-async fn instance_list(hash: BlockHash) -> anyhow::Result<HashMap<String, InstanceInfo>> {
-    println!("Fetching instance list for block hash: {:?}", hash);
 
-    let endpoint_node = Endpoint::from_str("http://127.0.0.1:20100")?;
-    let mut client = v2::Client::new(endpoint_node).await?;
-    let mut contracts = client.get_instance_list(&hash).await?;
+// Reads all balances and contract instances at the last finalized block and emits the balances as a `balances` event,
+// together with the block itself, so the UI is populated without waiting for the next block.
+async fn load_chain_state(
+    chain: &LocalChain,
+    window: &Window,
+    last_height: &mut Option<AbsoluteBlockHeight>,
+) -> anyhow::Result<ChainState> {
+    let consensus = chain.client.get().await?.get_consensus_info().await?;
+    let hash = consensus.last_finalized_block;
+    let height = consensus.last_finalized_block_height;
+
+    let balances = BalanceTracker::load(&chain.client, hash).await?;
+    chain.index.update_balances(height, balances.balances())?;
+    emit_balances(
+        chain,
+        window,
+        "balances",
+        hash,
+        height,
+        balances.balances().clone(),
+    );
+    let instances = InstanceTracker::load(&chain.client, &chain.schemas, hash).await?;
+    emit_new_block(
+        chain,
+        window,
+        last_height,
+        &instances,
+        instances.instances(),
+        hash,
+        height,
+    );
 
-    let mut amounts_map = HashMap::new();
-    while let Some(a) = contracts.response.next().await {
-        match a {
-            Ok(contract_addr) => {
-                println!("Fetching info for contract address: {:?}", contract_addr);
+    Ok(ChainState {
+        balances,
+        instances,
+        height,
+    })
+}
 
-                let info = client.get_instance_info(contract_addr, &hash).await?;
+// Indexes the blocks from `next_height` up to and including `last`, fetching the transactions and special outcomes of
+// each block once. Blocks past the height of `state` also advance the balances and contract instances. `next_height`
+// advances as soon as a block is stored, so after an error a block that was not stored is retried, and a stored one is
+// not inserted again even if emitting it failed.
+async fn index_blocks_until(
+    chain: &LocalChain,
+    window: &Window,
+    state: &mut ChainState,
+    last_height: &mut Option<AbsoluteBlockHeight>,
+    next_height: &mut AbsoluteBlockHeight,
+    last: AbsoluteBlockHeight,
+) -> anyhow::Result<()> {
+    while *next_height <= last {
+        let height = *next_height;
+        println!("Processing transactions for block: {}", height);
+
+        let block = chain
+            .client
+            .get()
+            .await?
+            .get_block_info(&height)
+            .await?
+            .response;
+        let summaries = transaction_info(&chain.client, height).await?;
+        let events = special_events(&chain.client, height).await?;
+
+        // Blocks up to the height the state was loaded at are already part of it. On an error the state is reloaded
+        // rather than emitting balances that may be stale.
+        let changes = if height > state.height {
+            let balances = state
+                .balances
+                .apply_block(&chain.client, &summaries, &events, block.block_hash)
+                .await?;
+            let instances = state
+                .instances
+                .apply_block(&chain.client, &chain.schemas, &summaries, block.block_hash)
+                .await?;
+            state.height = height;
+            Some((balances, instances))
+        } else {
+            None
+        };
 
-                let key_string = contract_addr.index.to_string();
-                let contract_info = info.response;
-                amounts_map.insert(key_string, contract_info);
+        let accounts: Vec<AccountAddress> = summaries
+            .iter()
+            .flat_map(|summary| summary.affected_addresses())
+            .collect();
+        let transactions = TransactionsInfo {
+            transactions: decode_transactions(&chain.schemas, height, summaries).await,
+            aliases: chain.aliases.aliases_of(&accounts),
+        };
 
-                println!(
-                    "Successfully fetched info for contract address: {:?}",
-                    contract_addr
-                );
+        chain.index.insert_block(
+            &IndexedBlock::from(&block),
+            &transactions.transactions,
+            &events,
+        )?;
+        next_height.height += 1;
+        window.emit("transactions", transactions)?;
+
+        if let Some((balances, instances)) = changes {
+            if let Err(e) = chain.index.update_balances(height, &balances) {
+                eprintln!("Failed to index balances: {}", e);
             }
-            Err(e) => {
-                println!(
-                    "Failed to get contract address for block hash {}: {}",
-                    hash, e
+            if !balances.is_empty() {
+                emit_balances(
+                    chain,
+                    window,
+                    "balances-changed",
+                    block.block_hash,
+                    height,
+                    balances,
                 );
-                return Err(anyhow::anyhow!("Failed to get contract address: {}", e));
             }
+            emit_new_block(
+                chain,
+                window,
+                last_height,
+                &state.instances,
+                &instances,
+                block.block_hash,
+                height,
+            );
         }
     }
 
-    if amounts_map.is_empty() {
-        println!("No contract instances found for the given block hash.");
-    } else {
-        println!(
-            "Total contract instances fetched for block hash {}: {}",
-            hash,
-            amounts_map.len()
-        );
+    Ok(())
+}
+
+fn emit_balances(
+    chain: &LocalChain,
+    window: &Window,
+    event: &str,
+    block_hash: BlockHash,
+    number: AbsoluteBlockHeight,
+    amounts: HashMap<AccountAddress, Amount>,
+) {
+    let balances = UiBalances {
+        hash: block_hash.to_string(),
+        number,
+        aliases: chain.aliases.aliases_of(amounts.keys()),
+        amounts,
+    };
+    if let Err(e) = window.emit(event, balances) {
+        eprintln!("Failed to emit {} event: {}", event, e);
     }
+}
 
-    println!("Finished fetching instance list for block hash: {:?}", hash);
+// Emits a block with all contract instances as a `new-block` event. Only the instances in `changes` are indexed again.
+fn emit_new_block(
+    chain: &LocalChain,
+    window: &Window,
+    last_height: &mut Option<AbsoluteBlockHeight>,
+    instances: &InstanceTracker,
+    changes: &HashMap<String, DecodedInstance>,
+    block_hash: BlockHash,
+    height: AbsoluteBlockHeight,
+) {
+    if last_height.map_or(false, |last| height <= last) {
+        return;
+    }
 
-    Ok(amounts_map)
+    if let Err(e) = chain.index.update_contracts(height, changes) {
+        eprintln!("Failed to index contract instances: {}", e);
+    }
+    let block_info = UiBlockInfo {
+        hash: block_hash.to_string(),
+        number: height,
+        contracts: instances.instances().clone(),
+    };
+    if let Err(e) = window.emit("new-block", block_info) {
+        eprintln!("Failed to emit new-block event: {}", e);
+    }
+    *last_height = Some(height);
 }
 
-async fn transaction_info(number: AbsoluteBlockHeight) -> anyhow::Result<TransactionsInfo> {
+/* ---------------------------------------------------- SUBTOOLS --------------------------------------------------------------------------- */
+
+#[derive(Debug, serde::Serialize, Clone)]
+struct UiBlockInfo {
+    hash: String,
+    number: AbsoluteBlockHeight,
+    contracts: HashMap<String, DecodedInstance>,
+}
+#[derive(Debug, serde::Serialize, Clone)]
+struct UiBalances {
+    hash: String,
+    number: AbsoluteBlockHeight,
+    amounts: HashMap<AccountAddress, Amount>,
+    aliases: HashMap<AccountAddress, String>,
+}
+#[derive(Debug, serde::Serialize, Clone)]
+struct TransactionsInfo {
+    transactions: Vec<DecodedTransaction>,
+    // Aliases of the accounts the transactions affect.
+    aliases: HashMap<AccountAddress, String>,
+}
+async fn transaction_info(
+    chain: &ChainClient,
+    number: AbsoluteBlockHeight,
+) -> anyhow::Result<Vec<BlockItemSummary>> {
     println!("Fetching transaction info for block height: {:?}", number);
 
-    let endpoint_node = Endpoint::from_str("http://127.0.0.1:20100")?;
-    let mut client = v2::Client::new(endpoint_node).await?;
+    let mut client = chain.get().await?;
     let res = client.get_block_transaction_events(&number).await?;
     let mut summaries: Vec<BlockItemSummary> = Vec::new(); // Created locally
 
@@ -736,94 +905,22 @@ async fn transaction_info(number: AbsoluteBlockHeight) -> anyhow::Result<Transac
         number
     );
 
-    Ok(TransactionsInfo {
-        transactions: summaries.to_vec(),
-    })
+    Ok(summaries)
 }
 
-async fn amount_info(hash: BlockHash) -> anyhow::Result<HashMap<AccountAddress, Amount>> {
-    println!("Fetching amount info for block hash: {:?}", hash);
-
-    let endpoint_node = Endpoint::from_str("http://127.0.0.1:20100")?;
-    let mut client = v2::Client::new(endpoint_node).await?;
-    let mut accounts = client.get_account_list(&hash).await?;
-
-    let mut amounts_map = HashMap::new();
-    while let Some(a) = accounts.response.next().await {
-        match a {
-            Ok(account_addr) => {
-                let addr = AccountIdentifier::Address(account_addr.clone());
-                let info = client.get_account_info(&addr, hash).await?;
-                let amt = info.response.account_amount;
-                amounts_map.insert(a?, amt);
-            }
-            Err(e) => {
-                println!("Failed to get account address. Error: {:?}", e);
-                return Err(anyhow::anyhow!("Failed to get account address: {}", e));
-            }
-        }
-    }
-
-    Ok(amounts_map)
-}
-
-async fn account_info() -> anyhow::Result<(BlockHash, AbsoluteBlockHeight)> {
-    let endpoint_node = Endpoint::from_str("http://127.0.0.1:20100").map_err(|e| {
-        println!("[ERROR] Failed to create endpoint: {:?}", e);
-        e
-    })?;
-
-    println!("[INFO] Endpoint created successfully.");
-
-    let mut client = v2::Client::new(endpoint_node).await.map_err(|e| {
-        println!("[ERROR] Failed to create client: {:?}", e);
-        e
-    })?;
-
-    println!("[INFO] Client created successfully.");
-
-    let mut accounts = client
-        .get_account_list(&v2::BlockIdentifier::LastFinal)
-        .await
-        .map_err(|e| {
-            println!("[ERROR] Failed to get account list: {:?}", e);
-            e
-        })?;
-
-    println!("[INFO] Account list retrieved successfully.");
-
-    let block = accounts.block_hash;
-    let mut account_addr: Option<AccountAddress> = None;
-    while let Some(a) = accounts.response.next().await {
-        account_addr = a.ok();
-    }
+async fn special_events(
+    chain: &ChainClient,
+    number: AbsoluteBlockHeight,
+) -> anyhow::Result<Vec<SpecialTransactionOutcome>> {
+    let mut client = chain.get().await?;
+    let mut outcomes = client.get_block_special_events(&number).await?.response;
 
-    if account_addr.is_none() {
-        println!("[WARN] No account address found.");
-        return Err(anyhow::anyhow!("No account address found"));
+    let mut events = Vec::new();
+    while let Some(outcome) = outcomes.next().await {
+        events.push(outcome?);
     }
 
-    let addr = AccountIdentifier::Address(account_addr.unwrap());
-
-    let info = client.get_account_info(&addr, block).await.map_err(|e| {
-        println!("[ERROR] Failed to get account info: {:?}", e);
-        e
-    })?;
-
-    println!("[INFO] Account info retrieved successfully.");
-
-    let hash = info.block_hash;
-
-    let blocks = client.get_block_info(&block).await.map_err(|e| {
-        println!("[ERROR] Failed to get block info: {:?}", e);
-        e
-    })?;
-
-    println!("[INFO] Block info retrieved successfully.");
-
-    let block_number = blocks.response.block_height;
-
-    Ok((hash, block_number))
+    Ok(events)
 }
 
 fn main() {
@@ -846,8 +943,61 @@ fn main() {
             verify_installation,
             launch_template,
             list_chain_folders,
-            kill_chain
+            kill_chain,
+            network::list_nodes,
+            network::pause_node,
+            network::resume_node,
+            network::stop_node,
+            network::start_node,
+            network::partition_network,
+            network::heal_network,
+            updates::protocol_update,
+            updates::list_scheduled_updates,
+            chain_parameters::update_chain_parameter,
+            chain_parameters::get_chain_parameters,
+            queries::get_block,
+            queries::list_blocks,
+            queries::get_transaction,
+            queries::list_account_transactions,
+            transactions::send_transfer,
+            keystore::list_accounts,
+            aliases::list_aliases,
+            aliases::set_alias,
+            aliases::remove_alias,
+            credentials::create_account,
+            bakers::register_baker,
+            bakers::update_baker_stake,
+            bakers::set_baker_open_status,
+            bakers::remove_baker,
+            delegation::add_delegation,
+            delegation::update_delegation,
+            delegation::remove_delegation,
+            delegation::list_pools,
+            export::export_account,
+            faucet::faucet,
+            faucet::get_faucet_amount,
+            faucet::set_faucet_amount,
+            contracts::deploy_module,
+            contracts::init_contract,
+            contracts::update_contract,
+            contracts::invoke_contract,
+            watch::watch_contract,
+            watch::unwatch_contract,
+            watch::list_contract_watches,
+            scenario::run_scenario,
+            scenario::attach_scenario
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app_handle, event| {
+            // Tauri exits the process without dropping the managed state, so the nodes are stopped here.
+            if let RunEvent::Exit = event {
+                let app_state = app_handle.state::<Arc<Mutex<AppState>>>();
+                let mut state = app_state.lock().unwrap();
+                for (_, chain) in state.chains.drain() {
+                    chain.client.close();
+                    chain.nodes.stop();
+                }
+            }
+        });
 }
@@ -0,0 +1,603 @@
+use crate::chain_client::ChainClient;
+use crate::genesis::GenesisConfig;
+use crate::settings::ChainSettings;
+use crate::{chain_name, running_chain, AppState};
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{State, Window};
+use tokio::process::{Child, Command as AsyncCommand};
+use tokio::time::Duration;
+
+/// Peer-to-peer port of the first node. Further nodes listen on the following ports.
+const FIRST_LISTEN_PORT: u16 = 8169;
+
+/// gRPC port of the first node, which [`crate::chain_client::NODE_GRPC_ENDPOINT`] points to. Further nodes serve gRPC
+/// on the following ports.
+const FIRST_GRPC_PORT: u16 = 20100;
+
+/// Folder inside a chain folder holding the data directories of all nodes but the first.
+const NODES_FOLDER: &str = "nodes";
+
+/// File in a node's data directory that its output is written to.
+const NODE_LOG_FILE: &str = "node.log";
+
+/// How often the supervisor checks whether the nodes are still running.
+const SUPERVISE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often a node that exits on its own is restarted before it is left stopped.
+const MAX_RESTARTS: u32 = 3;
+
+pub(crate) fn node_binary() -> &'static str {
+    if cfg!(target_os = "windows") {
+        r"C:\Program Files\Concordium\Node 6.0.4\concordium-node.exe"
+    } else if cfg!(target_os = "linux") {
+        "concordium-node"
+    } else {
+        "/usr/local/bin/concordium-node"
+    }
+}
+
+// One `concordium-node` process baking with one set of baker credentials. Paths are relative to the chain folder.
+struct Node {
+    baker_id: u64,
+    data_dir: PathBuf,
+    credentials: PathBuf,
+    listen_port: u16,
+    grpc_port: u16,
+    /// Peer-to-peer ports of the nodes it connects to on start.
+    peers: Vec<u16>,
+    child: Option<Child>,
+    /// Set while the process is suspended by [`NodeNetwork::pause`].
+    paused: bool,
+    restarts: u32,
+}
+
+impl Node {
+    fn spawn(&self, chain_folder: &Path) -> anyhow::Result<Child> {
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(chain_folder.join(&self.data_dir).join(NODE_LOG_FILE))?;
+
+        let mut command = AsyncCommand::new(node_binary());
+        command
+            .args(["--no-bootstrap=true", "--listen-port"])
+            .arg(self.listen_port.to_string())
+            .args(["--grpc2-listen-addr", "127.0.0.1", "--grpc2-listen-port"])
+            .arg(self.grpc_port.to_string())
+            .arg("--data-dir")
+            .arg(&self.data_dir)
+            .arg("--config-dir")
+            .arg(&self.data_dir)
+            .arg("--baker-credentials-file")
+            .arg(&self.credentials);
+        for peer in &self.peers {
+            command
+                .arg("--connect-to")
+                .arg(format!("127.0.0.1:{}", peer));
+        }
+        // A node is killed with its handle, e.g. when the chain fails to start after its nodes were launched.
+        let child = command
+            .kill_on_drop(true)
+            .current_dir(chain_folder)
+            .stdout(Stdio::from(log.try_clone()?))
+            .stderr(Stdio::from(log))
+            .spawn()?;
+        Ok(child)
+    }
+
+    fn is_running(&self) -> bool {
+        self.child.is_some()
+    }
+
+    // Suspends or continues the process, which keeps its connections open but stops it from baking and finalizing.
+    #[cfg(unix)]
+    fn signal(&mut self, signal: nix::sys::signal::Signal) -> anyhow::Result<()> {
+        let pid = self
+            .child
+            .as_ref()
+            .and_then(|child| child.id())
+            .ok_or_else(|| {
+                anyhow::anyhow!("The node of baker {} is not running.", self.baker_id)
+            })?;
+        nix::sys::signal::kill(nix::unistd::Pid::from_raw(pid as i32), signal)?;
+        Ok(())
+    }
+}
+
+fn find_node(nodes: &mut [Node], baker_id: u64) -> anyhow::Result<&mut Node> {
+    nodes
+        .iter_mut()
+        .find(|node| node.baker_id == baker_id)
+        .ok_or_else(|| anyhow::anyhow!("The chain runs no node for baker {}.", baker_id))
+}
+
+// LC1C queries the chain through the first node only, so pausing or stopping it would stall every command and event.
+fn ensure_not_connected(node: &Node) -> anyhow::Result<()> {
+    if node.grpc_port == FIRST_GRPC_PORT {
+        anyhow::bail!(
+            "LC1C is connected to the node of baker {}, so it cannot be paused or stopped. Choose another node.",
+            node.baker_id
+        );
+    }
+    Ok(())
+}
+
+/// Status of one node of a chain, as returned by [`list_nodes`].
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NodeInfo {
+    pub baker_id: u64,
+    pub listen_port: u16,
+    pub grpc_port: u16,
+    pub data_dir: PathBuf,
+    pub running: bool,
+    pub paused: bool,
+    /// Bakers whose nodes this node connects to on start.
+    pub peers: Vec<u64>,
+}
+
+/// Payload of the `node-exited` event, sent when a node stops without being asked to.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NodeExitedEvent {
+    pub chain: String,
+    pub baker_id: u64,
+    pub status: String,
+    pub restarted: bool,
+}
+
+/// The `concordium-node` processes of a chain.
+///
+/// By default a chain runs a single node baking with `baker-0-credentials.json`. In multi-node mode, set through the
+/// chain settings, every baker credentials file in the `out.bakerKeys` folder gets its own node with its own data
+/// directory and ports, connected to all the others. The first node always keeps the chain folder as data directory and
+/// the default ports, so the app talks to it in both modes. A supervisor restarts nodes that exit on their own until the
+/// network is [stopped](NodeNetwork::stop).
+#[derive(Clone)]
+pub(crate) struct NodeNetwork {
+    chain_folder: PathBuf,
+    nodes: Arc<Mutex<Vec<Node>>>,
+    stopped: Arc<AtomicBool>,
+}
+
+// Baker credentials files written by the genesis creator or `register_baker`, ordered by baker ID.
+fn baker_credentials(chain_folder: &Path) -> anyhow::Result<Vec<(u64, PathBuf)>> {
+    let genesis = GenesisConfig::read(chain_folder)?;
+    let bakers_folder = genesis.output("bakerKeys", "bakers");
+    let mut credentials: Vec<_> = std::fs::read_dir(chain_folder.join(bakers_folder))?
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let baker_id = name
+                .strip_prefix("baker-")?
+                .strip_suffix("-credentials.json")?
+                .parse::<u64>()
+                .ok()?;
+            Some((baker_id, Path::new(bakers_folder).join(name)))
+        })
+        .collect();
+    credentials.sort();
+    Ok(credentials)
+}
+
+impl NodeNetwork {
+    /// Starts the nodes of a chain whose genesis was already generated.
+    pub fn launch(chain_folder: &Path, window: Option<Window>) -> anyhow::Result<Self> {
+        let multi_node = ChainSettings::load(chain_folder)?.multi_node;
+        let mut credentials = baker_credentials(chain_folder)?;
+        if !multi_node {
+            credentials.retain(|(baker_id, _)| *baker_id == 0);
+        }
+        if credentials.is_empty() {
+            anyhow::bail!("No baker credentials found in {}.", chain_folder.display());
+        }
+
+        let genesis = GenesisConfig::read(chain_folder)?.output_path("genesis", "genesis.dat");
+        let listen_ports: Vec<u16> = (0..credentials.len() as u16)
+            .map(|i| FIRST_LISTEN_PORT + i)
+            .collect();
+
+        let mut nodes: Vec<Node> = Vec::with_capacity(credentials.len());
+        for (i, (baker_id, credentials)) in credentials.into_iter().enumerate() {
+            let data_dir = if i == 0 {
+                PathBuf::from(".")
+            } else {
+                let data_dir = Path::new(NODES_FOLDER).join(format!("baker-{}", baker_id));
+                std::fs::create_dir_all(chain_folder.join(&data_dir))?;
+                // Each node reads the genesis block from its own data directory.
+                let node_genesis = chain_folder.join(&data_dir).join("genesis.dat");
+                if !node_genesis.exists() {
+                    std::fs::copy(&genesis, node_genesis)?;
+                }
+                data_dir
+            };
+
+            let mut node = Node {
+                baker_id,
+                data_dir,
+                credentials,
+                listen_port: listen_ports[i],
+                grpc_port: FIRST_GRPC_PORT + i as u16,
+                peers: listen_ports
+                    .iter()
+                    .copied()
+                    .filter(|port| *port != listen_ports[i])
+                    .collect(),
+                child: None,
+                paused: false,
+                restarts: 0,
+            };
+            match node.spawn(chain_folder) {
+                Ok(child) => node.child = Some(child),
+                Err(e) => {
+                    // Leave no half-started network behind.
+                    for started in &mut nodes {
+                        if let Some(child) = started.child.as_mut() {
+                            let _ = child.start_kill();
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+            println!(
+                "Started the node of baker {} on port {}.",
+                node.baker_id, node.listen_port
+            );
+            nodes.push(node);
+        }
+
+        let network = NodeNetwork {
+            chain_folder: chain_folder.to_path_buf(),
+            nodes: Arc::new(Mutex::new(nodes)),
+            stopped: Arc::new(AtomicBool::new(false)),
+        };
+        tokio::spawn(network.clone().supervise(window));
+        Ok(network)
+    }
+
+    async fn supervise(self, window: Option<Window>) {
+        let chain = chain_name(&self.chain_folder).unwrap_or_default();
+        while !self.stopped.load(Ordering::SeqCst) {
+            tokio::time::sleep(SUPERVISE_INTERVAL).await;
+            if self.stopped.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let mut events = Vec::new();
+            for node in self.nodes.lock().unwrap().iter_mut() {
+                let status = match node.child.as_mut().map(|child| child.try_wait()) {
+                    Some(Ok(Some(status))) => status,
+                    _ => continue,
+                };
+                node.child = None;
+                eprintln!("The node of baker {} exited: {}", node.baker_id, status);
+
+                if node.restarts < MAX_RESTARTS {
+                    node.restarts += 1;
+                    match node.spawn(&self.chain_folder) {
+                        Ok(child) => node.child = Some(child),
+                        Err(e) => eprintln!(
+                            "Failed to restart the node of baker {}: {}",
+                            node.baker_id, e
+                        ),
+                    }
+                }
+                events.push(NodeExitedEvent {
+                    chain: chain.clone(),
+                    baker_id: node.baker_id,
+                    status: status.to_string(),
+                    restarted: node.is_running(),
+                });
+            }
+
+            if let Some(window) = &window {
+                for event in events {
+                    if let Err(e) = window.emit("node-exited", event) {
+                        eprintln!("Failed to emit node-exited event: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Kills all nodes and ends the supervisor. Returns the process ids of the nodes that were running.
+    pub fn stop(&self) -> Vec<u32> {
+        self.stopped.store(true, Ordering::SeqCst);
+        let mut stopped = Vec::new();
+        for node in self.nodes.lock().unwrap().iter_mut() {
+            if let Some(mut child) = node.child.take() {
+                stopped.extend(child.id());
+                if let Err(e) = child.start_kill() {
+                    eprintln!("Failed to stop the node of baker {}: {}", node.baker_id, e);
+                }
+            }
+        }
+        stopped
+    }
+
+    pub fn nodes(&self) -> Vec<NodeInfo> {
+        let nodes = self.nodes.lock().unwrap();
+        nodes
+            .iter()
+            .map(|node| NodeInfo {
+                baker_id: node.baker_id,
+                listen_port: node.listen_port,
+                grpc_port: node.grpc_port,
+                data_dir: node.data_dir.clone(),
+                running: node.is_running(),
+                paused: node.paused,
+                peers: nodes
+                    .iter()
+                    .filter(|peer| node.peers.contains(&peer.listen_port))
+                    .map(|peer| peer.baker_id)
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Suspends the process of a node, as if the validator lost power without its connections being closed.
+    #[cfg(unix)]
+    pub fn pause(&self, baker_id: u64) -> anyhow::Result<()> {
+        let mut nodes = self.nodes.lock().unwrap();
+        let node = find_node(&mut nodes, baker_id)?;
+        ensure_not_connected(node)?;
+        node.signal(nix::sys::signal::Signal::SIGSTOP)?;
+        node.paused = true;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    pub fn resume(&self, baker_id: u64) -> anyhow::Result<()> {
+        let mut nodes = self.nodes.lock().unwrap();
+        let node = find_node(&mut nodes, baker_id)?;
+        node.signal(nix::sys::signal::Signal::SIGCONT)?;
+        node.paused = false;
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    pub fn pause(&self, _baker_id: u64) -> anyhow::Result<()> {
+        anyhow::bail!("Pausing nodes is only supported on Linux and macOS. Stop the node instead.")
+    }
+
+    #[cfg(not(unix))]
+    pub fn resume(&self, _baker_id: u64) -> anyhow::Result<()> {
+        anyhow::bail!("Pausing nodes is only supported on Linux and macOS. Stop the node instead.")
+    }
+
+    /// Kills the process of a node. The supervisor leaves it stopped until [`NodeNetwork::start_node`] is called.
+    pub async fn stop_node(&self, baker_id: u64) -> anyhow::Result<()> {
+        let child = {
+            let mut nodes = self.nodes.lock().unwrap();
+            let node = find_node(&mut nodes, baker_id)?;
+            ensure_not_connected(node)?;
+            node.paused = false;
+            node.child.take()
+        };
+        match child {
+            Some(mut child) => Ok(child.kill().await?),
+            None => anyhow::bail!("The node of baker {} is not running.", baker_id),
+        }
+    }
+
+    /// Starts a stopped node again from its data directory, so it catches up with the blocks it missed.
+    pub fn start_node(&self, baker_id: u64) -> anyhow::Result<()> {
+        let mut nodes = self.nodes.lock().unwrap();
+        let node = find_node(&mut nodes, baker_id)?;
+        if node.is_running() {
+            anyhow::bail!("The node of baker {} is already running.", baker_id);
+        }
+        node.child = Some(node.spawn(&self.chain_folder)?);
+        node.restarts = 0;
+        Ok(())
+    }
+
+    /// Splits the network into groups of nodes that only connect to each other. Nodes missing from `groups` form one
+    /// more group.
+    ///
+    /// Nodes keep the connections they have, so every running node is restarted with its new peers, except the node
+    /// LC1C is connected to through `client`. That one is told over gRPC which peers to drop and which to connect to.
+    pub async fn partition(&self, client: &ChainClient, groups: &[Vec<u64>]) -> anyhow::Result<()> {
+        let mut connected = client.get().await?;
+        let (children, dropped, added) = {
+            let mut nodes = self.nodes.lock().unwrap();
+            let group_of =
+                |baker_id: u64| groups.iter().position(|group| group.contains(&baker_id));
+            for group in groups {
+                for baker_id in group {
+                    find_node(&mut nodes, *baker_id)?;
+                }
+            }
+
+            let members: Vec<(Option<usize>, u16)> = nodes
+                .iter()
+                .map(|node| (group_of(node.baker_id), node.listen_port))
+                .collect();
+            let mut children = Vec::new();
+            let mut dropped = Vec::new();
+            let mut added = Vec::new();
+            for node in nodes.iter_mut() {
+                let group = group_of(node.baker_id);
+                let peers: Vec<u16> = members
+                    .iter()
+                    .filter(|(other, port)| *other == group && *port != node.listen_port)
+                    .map(|(_, port)| *port)
+                    .collect();
+                if node.grpc_port == FIRST_GRPC_PORT {
+                    dropped = node
+                        .peers
+                        .iter()
+                        .copied()
+                        .filter(|port| !peers.contains(port))
+                        .collect();
+                    added = peers
+                        .iter()
+                        .copied()
+                        .filter(|port| !node.peers.contains(port))
+                        .collect();
+                } else {
+                    node.paused = false;
+                    if let Some(child) = node.child.take() {
+                        children.push((node.baker_id, child));
+                    }
+                }
+                node.peers = peers;
+            }
+            (children, dropped, added)
+        };
+
+        // Dropped peers are also removed from the addresses the node dials again, so it does not reconnect to them
+        // once they are restarted.
+        for port in dropped {
+            let peer = SocketAddr::from(([127, 0, 0, 1], port));
+            if let Err(e) = connected.peer_disconnect(peer).await {
+                eprintln!("Failed to disconnect from the node on port {}: {}", port, e);
+            }
+        }
+
+        // The ports have to be free before the nodes are started again.
+        let mut restart = Vec::with_capacity(children.len());
+        for (baker_id, mut child) in children {
+            if let Err(e) = child.kill().await {
+                eprintln!("Failed to stop the node of baker {}: {}", baker_id, e);
+            }
+            restart.push(baker_id);
+        }
+
+        let mut failed = Vec::new();
+        {
+            let mut nodes = self.nodes.lock().unwrap();
+            for baker_id in restart {
+                let node = find_node(&mut nodes, baker_id)?;
+                match node.spawn(&self.chain_folder) {
+                    Ok(child) => node.child = Some(child),
+                    Err(e) => {
+                        eprintln!("Failed to restart the node of baker {}: {}", baker_id, e);
+                        failed.push(baker_id);
+                    }
+                }
+            }
+        }
+
+        for port in added {
+            let peer = SocketAddr::from(([127, 0, 0, 1], port));
+            if let Err(e) = connected.peer_connect(peer).await {
+                eprintln!("Failed to connect to the node on port {}: {}", port, e);
+            }
+        }
+        if !failed.is_empty() {
+            anyhow::bail!("Failed to restart the nodes of bakers {:?}.", failed);
+        }
+        Ok(())
+    }
+
+    /// Reconnects all nodes to each other after [`NodeNetwork::partition`].
+    pub async fn heal(&self, client: &ChainClient) -> anyhow::Result<()> {
+        self.partition(client, &[]).await
+    }
+}
+
+/// Chooses between one node and one node per baker for the next launches of a chain.
+pub(crate) fn set_multi_node(chain_folder: &Path, multi_node: bool) -> anyhow::Result<()> {
+    ChainSettings::update(chain_folder, |settings| {
+        settings.multi_node = multi_node;
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn list_nodes(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+) -> Result<Vec<NodeInfo>, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    Ok(chain.nodes.nodes())
+}
+
+// Suspends a node's process. Blocks stop coming from its baker and, once too few finalizers remain, finalization stalls,
+// which shows in the `new-block` events.
+#[tauri::command]
+pub(crate) async fn pause_node(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    baker_id: u64,
+) -> Result<Vec<NodeInfo>, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    chain.nodes.pause(baker_id).map_err(|e| e.to_string())?;
+    Ok(chain.nodes.nodes())
+}
+
+#[tauri::command]
+pub(crate) async fn resume_node(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    baker_id: u64,
+) -> Result<Vec<NodeInfo>, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    chain.nodes.resume(baker_id).map_err(|e| e.to_string())?;
+    Ok(chain.nodes.nodes())
+}
+
+#[tauri::command]
+pub(crate) async fn stop_node(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    baker_id: u64,
+) -> Result<Vec<NodeInfo>, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    chain
+        .nodes
+        .stop_node(baker_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(chain.nodes.nodes())
+}
+
+#[tauri::command]
+pub(crate) async fn start_node(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    baker_id: u64,
+) -> Result<Vec<NodeInfo>, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    chain
+        .nodes
+        .start_node(baker_id)
+        .map_err(|e| e.to_string())?;
+    Ok(chain.nodes.nodes())
+}
+
+// Disconnects groups of nodes from each other, given as lists of baker IDs. Without a group holding two thirds of the
+// stake, finalization stalls until the network is healed.
+#[tauri::command]
+pub(crate) async fn partition_network(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    groups: Vec<Vec<u64>>,
+) -> Result<Vec<NodeInfo>, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    chain
+        .nodes
+        .partition(&chain.client, &groups)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(chain.nodes.nodes())
+}
+
+#[tauri::command]
+pub(crate) async fn heal_network(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+) -> Result<Vec<NodeInfo>, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    chain
+        .nodes
+        .heal(&chain.client)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(chain.nodes.nodes())
+}
@@ -0,0 +1,92 @@
+use crate::index_db::{IndexedBlock, IndexedBlockDetails, IndexedTransaction, Page};
+use crate::{running_chain, AppState};
+use concordium_rust_sdk::types::hashes::TransactionHash;
+use serde::Deserialize;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+const DEFAULT_PAGE_SIZE: u64 = 20;
+const MAX_PAGE_SIZE: u64 = 100;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Pagination {
+    #[serde(default)]
+    offset: u64,
+    limit: Option<u64>,
+}
+
+impl Pagination {
+    fn limit(&self) -> u64 {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE)
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn get_block(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    height: u64,
+) -> Result<IndexedBlockDetails, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    chain
+        .index
+        .block(height)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Block {} has not been indexed yet.", height))
+}
+
+// Lists the indexed blocks with heights between `from_height` and `to_height` (inclusive), latest first.
+#[tauri::command]
+pub(crate) async fn list_blocks(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    from_height: u64,
+    to_height: u64,
+    pagination: Option<Pagination>,
+) -> Result<Page<IndexedBlock>, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let pagination = pagination.unwrap_or_default();
+    chain
+        .index
+        .blocks(
+            from_height,
+            to_height,
+            pagination.offset,
+            pagination.limit(),
+        )
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn get_transaction(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    hash: String,
+) -> Result<IndexedTransaction, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let hash = TransactionHash::from_str(&hash).map_err(|e| e.to_string())?;
+    chain
+        .index
+        .transaction(&hash.to_string())
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transaction {} is not in a finalized block yet.", hash))
+}
+
+// Lists the indexed transactions affecting an account, latest first.
+#[tauri::command]
+pub(crate) async fn list_account_transactions(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    address: String,
+    pagination: Option<Pagination>,
+) -> Result<Page<IndexedTransaction>, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let address = chain.aliases.resolve(&address).map_err(|e| e.to_string())?;
+    let pagination = pagination.unwrap_or_default();
+    chain
+        .index
+        .account_transactions(&address, pagination.offset, pagination.limit())
+        .map_err(|e| e.to_string())
+}
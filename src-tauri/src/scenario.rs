@@ -0,0 +1,381 @@
+use crate::contracts::{deploy, init, parse_contract_address, read_module, update};
+use crate::settings::{AttachedScenario, ChainSettings};
+use crate::transactions::transfer;
+use crate::{running_chain, AppState, LocalChain};
+use concordium_rust_sdk::smart_contracts::common::{AccountAddress, Amount, ContractAddress};
+use concordium_rust_sdk::types::smart_contracts::ModuleReference;
+use concordium_rust_sdk::types::WalletAccount;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tauri::{State, Window};
+
+/// An ordered list of transactions to run against a chain, read from a TOML or JSON file.
+///
+/// Accounts are given by address, by their alias on the chain, e.g. `foundation`, or by the alias of their key file.
+/// Deployed modules and initialized contracts can be given a `name`, by which later steps refer to them instead of a
+/// module reference or contract address. Amounts are in CCD and relative `wasm` paths are resolved from the script's folder.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Scenario {
+    #[serde(alias = "step")]
+    pub steps: Vec<ScenarioStep>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub(crate) enum ScenarioStep {
+    Deploy {
+        sender: String,
+        wasm: PathBuf,
+        #[serde(default)]
+        name: Option<String>,
+    },
+    Init {
+        sender: String,
+        module: String,
+        contract: String,
+        #[serde(default)]
+        param: JsonValue,
+        #[serde(default)]
+        amount: Option<String>,
+        #[serde(default)]
+        name: Option<String>,
+        /// Energy limit of the transaction, see [`crate::contracts::init`].
+        #[serde(default)]
+        energy: Option<u64>,
+    },
+    Transfer {
+        sender: String,
+        to: Recipients,
+        amount: String,
+        #[serde(default)]
+        memo: Option<String>,
+    },
+    Update {
+        sender: String,
+        contract: String,
+        entrypoint: String,
+        #[serde(default)]
+        param: JsonValue,
+        #[serde(default)]
+        amount: Option<String>,
+    },
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub(crate) enum Recipients {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StepReport {
+    /// Position of the step in the script, starting from 1.
+    pub step: usize,
+    pub action: String,
+    pub outcome: JsonValue,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StepFailure {
+    pub step: usize,
+    pub action: String,
+    pub error: String,
+}
+
+/// What a scenario run did. The run stops at the first failing step, so `completed` holds the steps before it.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ScenarioReport {
+    pub script: PathBuf,
+    pub total_steps: usize,
+    pub completed: Vec<StepReport>,
+    pub failure: Option<StepFailure>,
+}
+
+impl Scenario {
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        let scenario = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&contents)?,
+            _ => toml::from_str(&contents)?,
+        };
+        Ok(scenario)
+    }
+}
+
+impl ScenarioStep {
+    fn action(&self) -> &'static str {
+        match self {
+            ScenarioStep::Deploy { .. } => "deploy",
+            ScenarioStep::Init { .. } => "init",
+            ScenarioStep::Transfer { .. } => "transfer",
+            ScenarioStep::Update { .. } => "update",
+        }
+    }
+}
+
+// Modules and contracts named by earlier steps of a run.
+#[derive(Default)]
+struct Names {
+    modules: HashMap<String, ModuleReference>,
+    contracts: HashMap<String, ContractAddress>,
+}
+
+impl Names {
+    fn module(&self, module: &str) -> anyhow::Result<ModuleReference> {
+        match self.modules.get(module) {
+            Some(module_ref) => Ok(*module_ref),
+            None => ModuleReference::from_str(module)
+                .map_err(|_| anyhow::anyhow!("Unknown module {}.", module)),
+        }
+    }
+
+    fn contract(&self, contract: &str) -> anyhow::Result<ContractAddress> {
+        match self.contracts.get(contract) {
+            Some(address) => Ok(*address),
+            None => parse_contract_address(contract)
+                .map_err(|_| anyhow::anyhow!("Unknown contract {}.", contract)),
+        }
+    }
+}
+
+// Accounts are resolved through the chain's aliases first, then through the aliases of the key files.
+fn account_address(chain: &LocalChain, account: &str) -> anyhow::Result<AccountAddress> {
+    if let Ok(address) = chain.aliases.resolve(account) {
+        return Ok(address);
+    }
+    chain
+        .keys
+        .by_alias(account)
+        .map(|stored| stored.keys.address)
+        .ok_or_else(|| anyhow::anyhow!("Unknown account {}.", account))
+}
+
+fn signer(chain: &LocalChain, account: &str) -> anyhow::Result<Arc<WalletAccount>> {
+    chain.keys.signer(&account_address(chain, account)?)
+}
+
+fn parse_amount(amount: Option<&str>) -> anyhow::Result<Amount> {
+    match amount {
+        Some(amount) => Amount::from_str(amount)
+            .map_err(|e| anyhow::anyhow!("Invalid amount {}: {}", amount, e)),
+        None => Ok(Amount::zero()),
+    }
+}
+
+/// Runs the steps of a script in order, stopping at the first one that fails.
+pub(crate) async fn run(chain: &LocalChain, script: &Path) -> anyhow::Result<ScenarioReport> {
+    let scenario = Scenario::read(script)?;
+    let base = script.parent().unwrap_or_else(|| Path::new("."));
+
+    let mut report = ScenarioReport {
+        script: script.to_path_buf(),
+        total_steps: scenario.steps.len(),
+        completed: Vec::new(),
+        failure: None,
+    };
+    let mut names = Names::default();
+    for (index, step) in scenario.steps.iter().enumerate() {
+        println!("Running scenario step {}: {}", index + 1, step.action());
+        match run_step(chain, base, &mut names, step).await {
+            Ok(outcome) => report.completed.push(StepReport {
+                step: index + 1,
+                action: step.action().to_string(),
+                outcome,
+            }),
+            Err(e) => {
+                eprintln!("Scenario step {} failed: {}", index + 1, e);
+                report.failure = Some(StepFailure {
+                    step: index + 1,
+                    action: step.action().to_string(),
+                    error: e.to_string(),
+                });
+                break;
+            }
+        }
+    }
+    Ok(report)
+}
+
+async fn run_step(
+    chain: &LocalChain,
+    base: &Path,
+    names: &mut Names,
+    step: &ScenarioStep,
+) -> anyhow::Result<JsonValue> {
+    let outcome = match step {
+        ScenarioStep::Deploy { sender, wasm, name } => {
+            let sender = signer(chain, sender)?;
+            let module = read_module(&base.join(wasm))?;
+            let module_ref = module.get_module_ref();
+            let outcome = deploy(&chain.client, &sender, module).await?;
+            if let Some(name) = name {
+                names.modules.insert(name.clone(), module_ref);
+            }
+            serde_json::to_value(outcome)?
+        }
+        ScenarioStep::Init {
+            sender,
+            module,
+            contract,
+            param,
+            amount,
+            name,
+            energy,
+        } => {
+            let sender = signer(chain, sender)?;
+            let contract_name = contract.strip_prefix("init_").unwrap_or(contract);
+            let outcome = init(
+                &chain.client,
+                &chain.schemas,
+                &sender,
+                names.module(module)?,
+                contract_name,
+                param,
+                parse_amount(amount.as_deref())?,
+                *energy,
+            )
+            .await?;
+            if let Some(name) = name {
+                names
+                    .contracts
+                    .insert(name.clone(), outcome.contract_address);
+            }
+            serde_json::to_value(outcome)?
+        }
+        ScenarioStep::Transfer {
+            sender,
+            to,
+            amount,
+            memo,
+        } => {
+            let sender = signer(chain, sender)?;
+            let amount = parse_amount(Some(amount.as_str()))?;
+            let recipients = match to {
+                Recipients::One(to) => vec![to.clone()],
+                Recipients::Many(to) => to.clone(),
+            };
+
+            let mut outcomes = Vec::with_capacity(recipients.len());
+            for recipient in &recipients {
+                let to = account_address(chain, recipient)?;
+                let outcome = transfer(&chain.client, &sender, to, amount, memo.as_deref()).await?;
+                if !outcome.success {
+                    anyhow::bail!(
+                        "The transfer to {} was rejected: {:?}",
+                        recipient,
+                        outcome.summary.is_rejected_account_transaction()
+                    );
+                }
+                outcomes.push(outcome);
+            }
+            serde_json::to_value(outcomes)?
+        }
+        ScenarioStep::Update {
+            sender,
+            contract,
+            entrypoint,
+            param,
+            amount,
+        } => {
+            let sender = signer(chain, sender)?;
+            let outcome = update(
+                &chain.client,
+                &chain.schemas,
+                &sender,
+                names.contract(contract)?,
+                entrypoint,
+                param,
+                parse_amount(amount.as_deref())?,
+            )
+            .await?;
+            serde_json::to_value(outcome)?
+        }
+    };
+    Ok(outcome)
+}
+
+/// Saves a script in a chain's settings, to be run once the chain is next launched.
+pub(crate) fn attach(chain_folder: &Path, script: Option<PathBuf>) -> anyhow::Result<()> {
+    if let Some(script) = &script {
+        Scenario::read(script)?;
+    }
+    ChainSettings::update(chain_folder, |settings| {
+        settings.scenario = script.map(|path| AttachedScenario {
+            path,
+            completed: false,
+        });
+    })
+}
+
+/// Runs the script attached to a chain once the node accepts connections, unless it already completed on this chain.
+/// The report is emitted as a `scenario-finished` event.
+pub(crate) fn spawn_attached(chain: &LocalChain, window: &Window) -> anyhow::Result<()> {
+    let script = match ChainSettings::load(&chain.folder)?.scenario {
+        Some(scenario) if !scenario.completed => scenario.path,
+        _ => return Ok(()),
+    };
+
+    let chain = chain.clone();
+    let window = window.clone();
+    tokio::spawn(async move {
+        if chain.client.connected().await.is_none() {
+            return;
+        }
+        let report = match run(&chain, &script).await {
+            Ok(report) => report,
+            Err(e) => {
+                eprintln!("Failed to run scenario {}: {}", script.display(), e);
+                return;
+            }
+        };
+
+        if report.failure.is_none() {
+            let marked = ChainSettings::update(&chain.folder, |settings| {
+                if let Some(scenario) = &mut settings.scenario {
+                    scenario.completed = true;
+                }
+            });
+            if let Err(e) = marked {
+                eprintln!("Failed to save the scenario state: {}", e);
+            }
+        }
+        if let Err(e) = window.emit("scenario-finished", report) {
+            eprintln!("Failed to emit scenario-finished event: {}", e);
+        }
+    });
+    Ok(())
+}
+
+// Runs a scenario script against a running chain and returns a report of the steps it ran. A failing step ends the run
+// and is described in the report's `failure`.
+#[tauri::command]
+pub(crate) async fn run_scenario(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    script: String,
+) -> Result<ScenarioReport, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    run(&chain, Path::new(&script))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Attaches a scenario script to a chain, which runs it the next time it is launched. Passing no script detaches it.
+#[tauri::command]
+pub(crate) async fn attach_scenario(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    script: Option<String>,
+) -> Result<(), String> {
+    let chain = running_chain(&app_state, &chain)?;
+    attach(&chain.folder, script.map(PathBuf::from)).map_err(|e| e.to_string())
+}
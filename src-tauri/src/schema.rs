@@ -0,0 +1,310 @@
+use crate::chain_client::ChainClient;
+use crate::contracts::instance_contract;
+use concordium_rust_sdk::smart_contracts::common::schema::{Type, VersionedModuleSchema};
+use concordium_rust_sdk::smart_contracts::common::{
+    AccountAddress, Amount, ContractAddress, Cursor, OwnedContractName, OwnedReceiveName,
+};
+use concordium_rust_sdk::smart_contracts::engine::utils::{
+    get_embedded_schema_v0, get_embedded_schema_v1,
+};
+use concordium_rust_sdk::types::smart_contracts::{
+    ContractEvent, InstanceInfo, ModuleReference, OwnedParameter, WasmVersion,
+};
+use concordium_rust_sdk::types::{
+    AbsoluteBlockHeight, AccountTransactionDetails, AccountTransactionEffects, BlockItemSummary,
+    BlockItemSummaryDetails, ContractTraceElement,
+};
+use concordium_rust_sdk::v2::BlockIdentifier;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+/// Reads the schema embedded in a deployed module. Returns `None` for modules built without a schema.
+pub(crate) async fn module_schema(
+    chain: &ChainClient,
+    module_ref: &ModuleReference,
+) -> anyhow::Result<Option<VersionedModuleSchema>> {
+    let mut client = chain.get().await?;
+    let module = client
+        .get_module_source(module_ref, &BlockIdentifier::LastFinal)
+        .await?
+        .response;
+
+    let schema = match module.version {
+        WasmVersion::V0 => get_embedded_schema_v0(module.source.as_ref()).ok(),
+        WasmVersion::V1 => get_embedded_schema_v1(module.source.as_ref()).ok(),
+    };
+    Ok(schema)
+}
+
+/// The embedded schemas of the modules deployed on a chain, each fetched from the node once.
+///
+/// Deployed modules never change, so a module's schema, or the lack of one, is kept for as long as the chain runs.
+#[derive(Clone)]
+pub(crate) struct SchemaCache {
+    chain: ChainClient,
+    schemas: Arc<tokio::sync::Mutex<HashMap<ModuleReference, Option<Arc<VersionedModuleSchema>>>>>,
+}
+
+impl SchemaCache {
+    pub fn new(chain: ChainClient) -> Self {
+        SchemaCache {
+            chain,
+            schemas: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub async fn get(
+        &self,
+        module_ref: &ModuleReference,
+    ) -> anyhow::Result<Option<Arc<VersionedModuleSchema>>> {
+        let mut schemas = self.schemas.lock().await;
+        if let Some(schema) = schemas.get(module_ref) {
+            return Ok(schema.clone());
+        }
+
+        let schema = module_schema(&self.chain, module_ref).await?.map(Arc::new);
+        schemas.insert(*module_ref, schema.clone());
+        Ok(schema)
+    }
+
+    /// The name of the contract at an address and the schema of its module, as of the given block.
+    async fn contract_schema(
+        &self,
+        address: ContractAddress,
+        block: &BlockIdentifier,
+    ) -> anyhow::Result<(String, Option<Arc<VersionedModuleSchema>>)> {
+        let (module_ref, contract_name) = instance_contract(&self.chain, address, block).await?;
+        Ok((contract_name, self.get(&module_ref).await?))
+    }
+}
+
+/// Encodes a JSON parameter with its schema type. Without a schema only an empty parameter can be given.
+pub(crate) fn encode_parameter(
+    schema: Option<&Type>,
+    json: &JsonValue,
+) -> anyhow::Result<OwnedParameter> {
+    let bytes = match (schema, json) {
+        (None, JsonValue::Null) | (Some(Type::Unit), JsonValue::Null) => Vec::new(),
+        (Some(schema), json) => schema
+            .serial_value(json)
+            .map_err(|e| anyhow::anyhow!("The parameter does not match its schema: {}", e))?,
+        (None, _) => anyhow::bail!("The module has no schema for this parameter."),
+    };
+    OwnedParameter::try_from(bytes).map_err(|_| anyhow::anyhow!("The parameter is too large."))
+}
+
+/// Decodes a parameter, return value or event with its schema type, falling back to hex when that is not possible.
+pub(crate) fn decode(schema: Option<&Type>, bytes: &[u8]) -> JsonValue {
+    schema
+        .and_then(|schema| schema.to_json(&mut Cursor::new(bytes)).ok())
+        .unwrap_or_else(|| JsonValue::String(hex::encode(bytes)))
+}
+
+/// A contract instance as shown in the contract view, with the state of V0 contracts decoded.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DecodedInstance {
+    pub name: OwnedContractName,
+    pub source_module: ModuleReference,
+    pub owner: AccountAddress,
+    pub amount: Amount,
+    pub methods: BTreeSet<OwnedReceiveName>,
+    /// State of a V0 contract, decoded with the contract's state schema. V1 contracts keep their state in a key-value
+    /// store that is not part of the instance info.
+    pub state: Option<JsonValue>,
+}
+
+pub(crate) async fn decode_instance(schemas: &SchemaCache, info: InstanceInfo) -> DecodedInstance {
+    let (name, source_module, owner, amount, methods, model) = match info {
+        InstanceInfo::V0 {
+            model,
+            owner,
+            amount,
+            methods,
+            name,
+            source_module,
+        } => (name, source_module, owner, amount, methods, Some(model)),
+        InstanceInfo::V1 {
+            owner,
+            amount,
+            methods,
+            name,
+            source_module,
+        } => (name, source_module, owner, amount, methods, None),
+    };
+
+    let state = match model {
+        Some(model) => {
+            let schema = schemas.get(&source_module).await.unwrap_or_else(|e| {
+                eprintln!(
+                    "Failed to get the schema of module {}: {}",
+                    source_module, e
+                );
+                None
+            });
+            let contract_name = name.as_contract_name().contract_name();
+            let state_schema = match schema.as_deref() {
+                Some(VersionedModuleSchema::V0(module)) => module
+                    .contracts
+                    .get(contract_name)
+                    .and_then(|contract| contract.state.as_ref()),
+                _ => None,
+            };
+            Some(decode(state_schema, &model))
+        }
+        None => None,
+    };
+
+    DecodedInstance {
+        name,
+        source_module,
+        owner,
+        amount,
+        methods,
+        state,
+    }
+}
+
+/// A transaction summary together with the decoded parameters and events of the contracts it called.
+///
+/// The summary is flattened, so this serializes to the summary with an extra `contractCalls` field.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DecodedTransaction {
+    #[serde(flatten)]
+    pub summary: BlockItemSummary,
+    pub contract_calls: Vec<DecodedContractCall>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DecodedContractCall {
+    pub address: ContractAddress,
+    /// The init or receive function called, or `None` for events logged before the contract called out to another one.
+    pub entrypoint: Option<String>,
+    /// Parameter of a receive function. Summaries do not record the parameter of `init` functions.
+    pub parameter: Option<JsonValue>,
+    pub events: Vec<JsonValue>,
+}
+
+// A contract call as recorded in a transaction summary, before decoding.
+struct ContractCall<'a> {
+    address: ContractAddress,
+    init_name: Option<&'a OwnedContractName>,
+    receive_name: Option<&'a OwnedReceiveName>,
+    parameter: Option<&'a OwnedParameter>,
+    events: &'a [ContractEvent],
+}
+
+fn contract_calls(summary: &BlockItemSummary) -> Vec<ContractCall<'_>> {
+    let effects = match &summary.details {
+        BlockItemSummaryDetails::AccountTransaction(AccountTransactionDetails {
+            effects, ..
+        }) => effects,
+        _ => return Vec::new(),
+    };
+
+    match effects {
+        AccountTransactionEffects::ContractInitialized { data } => vec![ContractCall {
+            address: data.address,
+            init_name: Some(&data.init_name),
+            receive_name: None,
+            parameter: None,
+            events: &data.events,
+        }],
+        AccountTransactionEffects::ContractUpdateIssued { effects } => effects
+            .iter()
+            .filter_map(|element| match element {
+                ContractTraceElement::Updated { data } => Some(ContractCall {
+                    address: data.address,
+                    init_name: None,
+                    receive_name: Some(&data.receive_name),
+                    parameter: Some(&data.message),
+                    events: &data.events,
+                }),
+                ContractTraceElement::Interrupted { address, events } => Some(ContractCall {
+                    address: *address,
+                    init_name: None,
+                    receive_name: None,
+                    parameter: None,
+                    events,
+                }),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Decodes the contract calls of the transactions in a block with the schemas of the called contracts. Contracts whose
+/// schema cannot be found have their parameters and events shown as hex.
+pub(crate) async fn decode_transactions(
+    schemas: &SchemaCache,
+    height: AbsoluteBlockHeight,
+    summaries: Vec<BlockItemSummary>,
+) -> Vec<DecodedTransaction> {
+    let block = BlockIdentifier::AbsoluteHeight(height);
+    let mut contracts: HashMap<ContractAddress, Option<(String, Arc<VersionedModuleSchema>)>> =
+        HashMap::new();
+
+    let mut decoded = Vec::with_capacity(summaries.len());
+    for summary in summaries {
+        let mut calls = Vec::new();
+        for call in contract_calls(&summary) {
+            if let Entry::Vacant(entry) = contracts.entry(call.address) {
+                let schema = match schemas.contract_schema(call.address, &block).await {
+                    Ok((contract_name, schema)) => schema.map(|schema| (contract_name, schema)),
+                    Err(e) => {
+                        eprintln!(
+                            "Failed to get the schema of contract {}: {}",
+                            call.address, e
+                        );
+                        None
+                    }
+                };
+                entry.insert(schema);
+            }
+            let schema = contracts[&call.address].as_ref();
+
+            let event_schema = schema
+                .and_then(|(contract_name, schema)| schema.get_event_schema(contract_name).ok());
+            let entrypoint = call
+                .receive_name
+                .map(|name| name.as_receive_name().entrypoint_name().to_string());
+            let parameter_schema = match (schema, &entrypoint) {
+                (Some((contract_name, schema)), Some(entrypoint)) => schema
+                    .get_receive_param_schema(contract_name, entrypoint)
+                    .ok(),
+                _ => None,
+            };
+
+            calls.push(DecodedContractCall {
+                address: call.address,
+                entrypoint: call
+                    .init_name
+                    .map(|name| name.as_contract_name().get_chain_name().to_string())
+                    .or_else(|| {
+                        call.receive_name
+                            .map(|name| name.as_receive_name().get_chain_name().to_string())
+                    }),
+                parameter: call
+                    .parameter
+                    .map(|parameter| decode(parameter_schema.as_ref(), parameter.as_ref())),
+                events: call
+                    .events
+                    .iter()
+                    .map(|event| decode(event_schema.as_ref(), event.as_ref()))
+                    .collect(),
+            });
+        }
+
+        decoded.push(DecodedTransaction {
+            summary,
+            contract_calls: calls,
+        });
+    }
+    decoded
+}
@@ -0,0 +1,80 @@
+use crate::updates::ScheduledUpdate;
+use crate::watch::ContractWatch;
+use concordium_rust_sdk::smart_contracts::common::{AccountAddress, Amount};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// File name of the LC1C settings inside a chain folder.
+pub(crate) const SETTINGS_FILE: &str = "lc1c-settings.json";
+
+/// Held while the settings of any chain are read, changed and written back, see [`ChainSettings::update`].
+static SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Per-chain settings of LC1C, kept next to the chain data so they survive restarts.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ChainSettings {
+    /// Amount the faucet sends when no amount is given.
+    #[serde(default)]
+    pub faucet_amount: Option<Amount>,
+    /// Module files that are redeployed whenever they change.
+    #[serde(default)]
+    pub contract_watches: Vec<ContractWatch>,
+    /// Scenario script run once the chain is launched.
+    #[serde(default)]
+    pub scenario: Option<AttachedScenario>,
+    /// Account aliases, see [`crate::aliases::AliasRegistry`].
+    #[serde(default)]
+    pub aliases: BTreeMap<AccountAddress, String>,
+    /// Whether the chain runs one node per baker, see [`crate::network::NodeNetwork`].
+    #[serde(default)]
+    pub multi_node: bool,
+    /// Updates submitted with the chain's update keys that have not taken effect yet.
+    #[serde(default)]
+    pub scheduled_updates: Vec<ScheduledUpdate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AttachedScenario {
+    pub path: PathBuf,
+    /// Set once the script ran without failures, so relaunching the chain does not run it again.
+    #[serde(default)]
+    pub completed: bool,
+}
+
+impl ChainSettings {
+    /// Reads the settings of a chain, falling back to the defaults if none were saved yet.
+    pub fn load(chain_folder: &Path) -> anyhow::Result<Self> {
+        let path = chain_folder.join(SETTINGS_FILE);
+        if !path.exists() {
+            return Ok(ChainSettings::default());
+        }
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// Applies a change to the saved settings of a chain.
+    ///
+    /// Commands and background tasks change the settings concurrently, so every change reads the settings again and
+    /// writes them back under one lock, instead of overwriting another change made in between.
+    pub fn update<T>(
+        chain_folder: &Path,
+        change: impl FnOnce(&mut ChainSettings) -> T,
+    ) -> anyhow::Result<T> {
+        let _guard = SETTINGS_LOCK.lock().unwrap();
+        let mut settings = ChainSettings::load(chain_folder)?;
+        let result = change(&mut settings);
+        settings.save(chain_folder)?;
+        Ok(result)
+    }
+
+    pub fn save(&self, chain_folder: &Path) -> anyhow::Result<()> {
+        std::fs::write(
+            chain_folder.join(SETTINGS_FILE),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+}
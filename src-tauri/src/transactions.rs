@@ -0,0 +1,152 @@
+use crate::chain_client::ChainClient;
+use crate::{running_chain, AppState};
+use concordium_rust_sdk::common::types::TransactionTime;
+use concordium_rust_sdk::smart_contracts::common::{AccountAddress, Amount};
+use concordium_rust_sdk::types::transactions::{send, BlockItem, EncodedPayload};
+use concordium_rust_sdk::types::{BlockItemSummary, Memo, Nonce, WalletAccount};
+use serde::Serialize;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+/// How long a submitted transaction stays valid.
+const TRANSACTION_EXPIRY_SECONDS: u64 = 300;
+
+/// Outcome of a transaction submitted by LC1C, once it is finalized.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TransactionOutcome {
+    pub transaction_hash: String,
+    pub block_hash: String,
+    pub success: bool,
+    pub summary: BlockItemSummary,
+}
+
+pub(crate) fn expiry() -> anyhow::Result<TransactionTime> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    Ok(TransactionTime::from_seconds(
+        now + TRANSACTION_EXPIRY_SECONDS,
+    ))
+}
+
+pub(crate) async fn next_nonce(
+    chain: &ChainClient,
+    address: &AccountAddress,
+) -> anyhow::Result<Nonce> {
+    let nonce = chain
+        .get()
+        .await?
+        .get_next_account_sequence_number(address)
+        .await?
+        .nonce;
+    Ok(nonce)
+}
+
+/// Submits a signed transaction and waits for it to be finalized.
+pub(crate) async fn submit_and_wait(
+    chain: &ChainClient,
+    item: BlockItem<EncodedPayload>,
+) -> anyhow::Result<TransactionOutcome> {
+    let mut client = chain.get().await?;
+    let hash = client.send_block_item(&item).await?;
+    println!("Submitted transaction {}, waiting for finalization.", hash);
+
+    let (block_hash, summary) = client.wait_until_finalized(&hash).await?;
+    Ok(TransactionOutcome {
+        transaction_hash: hash.to_string(),
+        block_hash: block_hash.to_string(),
+        success: summary.is_success(),
+        summary,
+    })
+}
+
+/// Encodes a memo as a CBOR text string, which is how wallets and `concordium-client` display memos.
+fn encode_memo(memo: &str) -> anyhow::Result<Memo> {
+    let bytes = memo.as_bytes();
+    let mut encoded = match bytes.len() {
+        len @ 0..=23 => vec![0x60 | len as u8],
+        len @ 24..=255 => vec![0x78, len as u8],
+        len => vec![0x79, (len >> 8) as u8, len as u8],
+    };
+    encoded.extend_from_slice(bytes);
+    Memo::try_from(encoded).map_err(|_| anyhow::anyhow!("The memo is longer than 256 bytes."))
+}
+
+// Transfers CCD between two accounts, signing with the sender's keys from the chain folder. `amount` is given in CCD.
+#[tauri::command]
+pub(crate) async fn send_transfer(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    from: String,
+    to: String,
+    amount: String,
+    memo: Option<String>,
+) -> Result<TransactionOutcome, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let from = chain.aliases.resolve(&from).map_err(|e| e.to_string())?;
+    let to = chain.aliases.resolve(&to).map_err(|e| e.to_string())?;
+    let amount = Amount::from_str(&amount).map_err(|e| format!("Invalid amount: {}", e))?;
+
+    let sender = chain.keys.signer(&from).map_err(|e| e.to_string())?;
+    transfer(&chain.client, &sender, to, amount, memo.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub(crate) async fn transfer(
+    chain: &ChainClient,
+    sender: &WalletAccount,
+    to: AccountAddress,
+    amount: Amount,
+    memo: Option<&str>,
+) -> anyhow::Result<TransactionOutcome> {
+    let nonce = next_nonce(chain, &sender.address).await?;
+    let expiry = expiry()?;
+
+    let transaction = match memo {
+        Some(memo) => send::transfer_with_memo(
+            sender,
+            sender.address,
+            nonce,
+            expiry,
+            to,
+            amount,
+            encode_memo(memo)?,
+        ),
+        None => send::transfer(sender, sender.address, nonce, expiry, to, amount),
+    };
+
+    submit_and_wait(chain, BlockItem::AccountTransaction(transaction)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded(memo: &str) -> Vec<u8> {
+        let memo = encode_memo(memo).unwrap();
+        let bytes: &[u8] = memo.as_ref();
+        bytes.to_vec()
+    }
+
+    #[test]
+    fn short_memos_have_the_length_in_the_header_byte() {
+        assert_eq!(encoded(""), vec![0x60]);
+        assert_eq!(encoded("hi"), vec![0x62, b'h', b'i']);
+        assert_eq!(encoded(&"a".repeat(23))[0], 0x77);
+    }
+
+    #[test]
+    fn longer_memos_have_a_one_byte_length() {
+        assert_eq!(encoded(&"a".repeat(24))[..2], [0x78, 24]);
+        assert_eq!(encoded(&"a".repeat(254))[..2], [0x78, 254]);
+    }
+
+    #[test]
+    fn encoded_memos_over_256_bytes_are_rejected() {
+        assert!(encode_memo(&"a".repeat(255)).is_err());
+        // Would need the two-byte length header 0x79.
+        assert!(encode_memo(&"a".repeat(256)).is_err());
+    }
+}
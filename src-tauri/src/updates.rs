@@ -0,0 +1,389 @@
+use crate::genesis::GenesisConfig;
+use crate::settings::ChainSettings;
+use crate::transactions::{expiry, submit_and_wait, TransactionOutcome};
+use crate::{chain_name, running_chain, AppState, LocalChain};
+use concordium_rust_sdk::common::types::TransactionTime;
+use concordium_rust_sdk::types::hashes::Hash;
+use concordium_rust_sdk::types::transactions::{update, BlockItem};
+use concordium_rust_sdk::types::{
+    AccessStructure, AuthorizationsV0, ProtocolUpdate, UpdateKeyPair, UpdateKeysIndex,
+    UpdatePayload, UpdatePublicKey, UpdateSequenceNumber,
+};
+use concordium_rust_sdk::v2::BlockIdentifier;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{State, Window};
+use tokio::time::Duration;
+
+/// How far in the future an update takes effect if no effective time is given.
+const DEFAULT_UPDATE_DELAY_SECONDS: u64 = 60;
+
+/// How often the chain is checked for scheduled updates that took effect.
+const TRACK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Where the specifications of the protocol versions are published. The node recognizes a protocol update by the
+/// SHA-256 hash of its specification.
+const PROTOCOL_SPECIFICATION_URL: &str =
+    "https://github.com/Concordium/concordium-update-proposals/blob/main/updates";
+const PROTOCOL_SPECIFICATION_RAW_URL: &str =
+    "https://raw.githubusercontent.com/Concordium/concordium-update-proposals/main/updates";
+
+/// Consensus parameters a chain gets when it switches to protocol version 6, which introduces them. They follow the
+/// values used on the public chains, except that a small local chain finalizes with a single finalizer.
+const P6_TIMEOUT_BASE_MILLIS: u64 = 10_000;
+const P6_TIMEOUT_INCREASE: (u64, u64) = (6, 5);
+const P6_TIMEOUT_DECREASE: (u64, u64) = (1, 2);
+const P6_MIN_BLOCK_TIME_MILLIS: u64 = 2_000;
+const P6_BLOCK_ENERGY_LIMIT: u64 = 3_000_000;
+const P6_MIN_FINALIZERS: u32 = 1;
+const P6_MAX_FINALIZERS: u32 = 1_000;
+/// In parts per hundred thousand of the total stake, i.e. 1%.
+const P6_FINALIZERS_RELATIVE_STAKE_THRESHOLD: u32 = 1_000;
+
+/// An update submitted by LC1C that has not taken effect yet, kept in the chain settings.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ScheduledUpdate {
+    /// What the update changes, e.g. `protocol`.
+    pub update: String,
+    pub effective_time: TransactionTime,
+    pub transaction_hash: String,
+    /// The protocol version the chain switches to, for protocol updates.
+    #[serde(default)]
+    pub target_protocol: Option<u64>,
+}
+
+/// Payload of the `protocol-updated` event, sent once the chain runs the new protocol version.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ProtocolUpdatedEvent {
+    pub chain: String,
+    pub protocol_version: u64,
+    pub genesis_index: u32,
+}
+
+/// Payload of the `chain-parameter-updated` event, sent once the effective time of a parameter update has passed.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ParameterUpdatedEvent {
+    pub chain: String,
+    pub update: String,
+    pub transaction_hash: String,
+}
+
+fn now_seconds() -> anyhow::Result<u64> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+}
+
+/// The access structure among the level 2 keys that authorizes updates with the given payload.
+fn access_structure<'a>(
+    payload: &UpdatePayload,
+    authorizations: &'a AuthorizationsV0,
+) -> anyhow::Result<&'a AccessStructure> {
+    match payload {
+        UpdatePayload::Protocol(_) => Ok(&authorizations.protocol),
+        UpdatePayload::EuroPerEnergy(_) => Ok(&authorizations.euro_per_energy),
+        UpdatePayload::MicroGTUPerEuro(_) => Ok(&authorizations.micro_gtu_per_euro),
+        UpdatePayload::TransactionFeeDistribution(_) => {
+            Ok(&authorizations.transaction_fee_distribution)
+        }
+        UpdatePayload::PoolParametersCPV1(_) => Ok(&authorizations.pool_parameters),
+        // From protocol version 6 the consensus parameters take over the access structure of the election difficulty.
+        UpdatePayload::BlockEnergyLimitCPV2(_) => Ok(&authorizations.election_difficulty),
+        _ => anyhow::bail!("LC1C does not sign updates of this kind."),
+    }
+}
+
+/// The level 2 update keys in the `out.updateKeys` folder that may sign the given kind of update, with their index in
+/// the chain's list of level 2 keys. Only as many keys as the threshold of the update's access structure are used.
+pub(crate) async fn update_signer(
+    chain: &LocalChain,
+    update_name: &str,
+    payload: &UpdatePayload,
+) -> anyhow::Result<Vec<(UpdateKeysIndex, UpdateKeyPair)>> {
+    let parameters = chain
+        .client
+        .get()
+        .await?
+        .get_block_chain_parameters(&BlockIdentifier::LastFinal)
+        .await?
+        .response;
+    let authorizations = parameters.common_update_keys();
+    let access = access_structure(payload, authorizations)?;
+    let threshold = usize::from(u16::from(access.threshold));
+
+    let keys_folder = GenesisConfig::read(&chain.folder)?.output_path("updateKeys", "update-keys");
+    let mut signer = Vec::new();
+    for entry in std::fs::read_dir(&keys_folder)? {
+        let path = entry?.path();
+        if path.extension().map_or(true, |ext| ext != "json") {
+            continue;
+        }
+        // Root and level 1 keys in the folder do not appear in the list of level 2 keys and are skipped.
+        let key: UpdateKeyPair = match serde_json::from_str(&std::fs::read_to_string(&path)?) {
+            Ok(key) => key,
+            Err(_) => continue,
+        };
+        let public = UpdatePublicKey::from(&key);
+        let index = match authorizations.keys.iter().position(|k| *k == public) {
+            Some(index) => UpdateKeysIndex {
+                index: index as u16,
+            },
+            None => continue,
+        };
+        if access.authorized_keys.contains(&index) {
+            signer.push((index, key));
+        }
+        if signer.len() == threshold {
+            return Ok(signer);
+        }
+    }
+    anyhow::bail!(
+        "Only {} of the {} update keys needed for {} updates found in {}.",
+        signer.len(),
+        threshold,
+        update_name,
+        keys_folder.display()
+    )
+}
+
+/// Signs an update with the chain's update keys, submits it and waits for it to be finalized. The update is recorded
+/// as scheduled until it takes effect.
+pub(crate) async fn submit_update(
+    chain: &LocalChain,
+    update_name: &str,
+    sequence_number: UpdateSequenceNumber,
+    effective_time: Option<u64>,
+    payload: UpdatePayload,
+    target_protocol: Option<u64>,
+) -> anyhow::Result<TransactionOutcome> {
+    let now = now_seconds()?;
+    let effective_time = effective_time.unwrap_or(now + DEFAULT_UPDATE_DELAY_SECONDS);
+    if effective_time <= now {
+        anyhow::bail!("The effective time of an update must be in the future.");
+    }
+    // The update has to be included in a block before it takes effect.
+    let timeout = expiry()?.seconds.min(effective_time - 1);
+
+    let keys = update_signer(chain, update_name, &payload).await?;
+    let instruction = update::update(
+        keys.as_slice(),
+        sequence_number,
+        TransactionTime::from_seconds(effective_time),
+        TransactionTime::from_seconds(timeout),
+        payload,
+    );
+    let outcome = submit_and_wait(&chain.client, BlockItem::UpdateInstruction(instruction)).await?;
+
+    if outcome.success {
+        let scheduled = ScheduledUpdate {
+            update: update_name.to_string(),
+            effective_time: TransactionTime::from_seconds(effective_time),
+            transaction_hash: outcome.transaction_hash.clone(),
+            target_protocol,
+        };
+        ChainSettings::update(&chain.folder, |settings| {
+            settings.scheduled_updates.push(scheduled);
+        })?;
+    }
+    Ok(outcome)
+}
+
+// The node only accepts the specification published for each protocol version, identified by its hash.
+async fn protocol_specification(target_version: u64) -> anyhow::Result<Hash> {
+    let url = format!("{}/P{}.txt", PROTOCOL_SPECIFICATION_RAW_URL, target_version);
+    let response = reqwest::get(&url).await?.error_for_status()?;
+    let specification = response.bytes().await?;
+    let digest: [u8; 32] = Sha256::digest(&specification).into();
+    Ok(Hash::from(digest))
+}
+
+/// The auxiliary data of an update to the given protocol version, i.e. the parameters the new version introduces.
+///
+/// Updates to versions 2, 3 and 5 have none. Version 6 gets its consensus parameters, serialized big-endian as the
+/// timeout base in milliseconds, the timeout increase and decrease as numerator and denominator, the minimum block
+/// time in milliseconds and the block energy limit, followed by the finalization committee parameters: minimum and
+/// maximum number of finalizers and the relative stake threshold.
+fn auxiliary_data(target_version: u64) -> anyhow::Result<Vec<u8>> {
+    match target_version {
+        2 | 3 | 5 => Ok(Vec::new()),
+        6 => {
+            let mut data = Vec::new();
+            for value in [
+                P6_TIMEOUT_BASE_MILLIS,
+                P6_TIMEOUT_INCREASE.0,
+                P6_TIMEOUT_INCREASE.1,
+                P6_TIMEOUT_DECREASE.0,
+                P6_TIMEOUT_DECREASE.1,
+                P6_MIN_BLOCK_TIME_MILLIS,
+                P6_BLOCK_ENERGY_LIMIT,
+            ] {
+                data.extend_from_slice(&value.to_be_bytes());
+            }
+            for value in [
+                P6_MIN_FINALIZERS,
+                P6_MAX_FINALIZERS,
+                P6_FINALIZERS_RELATIVE_STAKE_THRESHOLD,
+            ] {
+                data.extend_from_slice(&value.to_be_bytes());
+            }
+            Ok(data)
+        }
+        _ => anyhow::bail!(
+            "Updating to protocol version {} is not supported, since LC1C cannot build the parameters it introduces.",
+            target_version
+        ),
+    }
+}
+
+/// Submits an update to the given protocol version. The specification hash is computed from the published
+/// specification unless it is given.
+pub(crate) async fn submit_protocol_update(
+    chain: &LocalChain,
+    target_version: u64,
+    effective_time: Option<u64>,
+    specification_hash: Option<Hash>,
+) -> anyhow::Result<TransactionOutcome> {
+    let mut client = chain.client.get().await?;
+    let current = u64::from(client.get_consensus_info().await?.protocol_version);
+    if target_version != current + 1 {
+        anyhow::bail!(
+            "The chain runs protocol version {}, so it can only be updated to version {}.",
+            current,
+            current + 1
+        );
+    }
+    let specification_auxiliary_data = auxiliary_data(target_version)?;
+    let sequence_number = client
+        .get_next_update_sequence_numbers(&BlockIdentifier::LastFinal)
+        .await?
+        .response
+        .protocol;
+
+    let specification_hash = match specification_hash {
+        Some(hash) => hash,
+        None => protocol_specification(target_version).await?,
+    };
+    let payload = UpdatePayload::Protocol(ProtocolUpdate {
+        message: format!("Update to protocol version {}", target_version),
+        specification_url: format!("{}/P{}.txt", PROTOCOL_SPECIFICATION_URL, target_version),
+        specification_hash,
+        specification_auxiliary_data,
+    });
+
+    submit_update(
+        chain,
+        "protocol",
+        sequence_number,
+        effective_time,
+        payload,
+        Some(target_version),
+    )
+    .await
+}
+
+/// Follows the updates scheduled on a chain while it runs, dropping them from the settings once they took effect.
+/// Protocol updates are announced with a `protocol-updated` event and parameter updates with a
+/// `chain-parameter-updated` event.
+pub(crate) fn spawn_update_tracker(chain: &LocalChain, window: &Window) {
+    let chain = chain.clone();
+    let window = window.clone();
+    tokio::spawn(async move {
+        while !chain.client.is_closed() {
+            tokio::time::sleep(TRACK_INTERVAL).await;
+            if let Err(e) = track_updates(&chain, &window).await {
+                eprintln!("Failed to check the scheduled updates: {}", e);
+            }
+        }
+    });
+}
+
+async fn track_updates(chain: &LocalChain, window: &Window) -> anyhow::Result<()> {
+    let scheduled = ChainSettings::load(&chain.folder)?.scheduled_updates;
+    if scheduled.is_empty() {
+        return Ok(());
+    }
+
+    let consensus = chain.client.get().await?.get_consensus_info().await?;
+    let protocol_version = u64::from(consensus.protocol_version);
+    let now = now_seconds()?;
+    let took_effect = |update: &ScheduledUpdate| match update.target_protocol {
+        Some(target) => protocol_version >= target,
+        None => update.effective_time.seconds <= now,
+    };
+    if !scheduled.iter().any(took_effect) {
+        return Ok(());
+    }
+
+    // The settings are read again, since updates may have been scheduled meanwhile.
+    let done = ChainSettings::update(&chain.folder, |settings| {
+        let (done, pending) = settings
+            .scheduled_updates
+            .drain(..)
+            .partition::<Vec<_>, _>(took_effect);
+        settings.scheduled_updates = pending;
+        done
+    })?;
+    let protocol_updated = done.iter().any(|update| update.target_protocol.is_some());
+    let parameters_updated = done
+        .into_iter()
+        .filter(|update| update.target_protocol.is_none());
+
+    let chain_name = chain_name(&chain.folder).unwrap_or_default();
+    for update in parameters_updated {
+        let event = ParameterUpdatedEvent {
+            chain: chain_name.clone(),
+            update: update.update,
+            transaction_hash: update.transaction_hash,
+        };
+        window.emit("chain-parameter-updated", event)?;
+    }
+
+    if protocol_updated {
+        println!(
+            "The chain switched to protocol version {}.",
+            protocol_version
+        );
+        let event = ProtocolUpdatedEvent {
+            chain: chain_name,
+            protocol_version,
+            genesis_index: consensus.genesis_index.height,
+        };
+        window.emit("protocol-updated", event)?;
+    }
+    Ok(())
+}
+
+// Updates a running chain to the next protocol version, signing with the update keys in the chain folder.
+// `effective_time` is a Unix timestamp in seconds and defaults to a minute from now. Fetching the specification needs
+// network access unless its hash is given as hex.
+#[tauri::command]
+pub(crate) async fn protocol_update(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    target_version: u64,
+    effective_time: Option<u64>,
+    specification_hash: Option<String>,
+) -> Result<TransactionOutcome, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let specification_hash = match specification_hash {
+        Some(hash) => Some(Hash::from_str(&hash).map_err(|e| e.to_string())?),
+        None => None,
+    };
+    submit_protocol_update(&chain, target_version, effective_time, specification_hash)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn list_scheduled_updates(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+) -> Result<Vec<ScheduledUpdate>, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let settings = ChainSettings::load(&chain.folder).map_err(|e| e.to_string())?;
+    Ok(settings.scheduled_updates)
+}
@@ -0,0 +1,297 @@
+use crate::contracts::{
+    deploy, init, read_module, update_with_parameter, ContractOutcome, DeployOutcome,
+};
+use crate::settings::ChainSettings;
+use crate::{chain_name, running_chain, AppState, LocalChain};
+use concordium_rust_sdk::smart_contracts::common::{AccountAddress, Amount, ContractAddress};
+use concordium_rust_sdk::types::smart_contracts::{ModuleReference, OwnedParameter, WasmModule};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tauri::{State, Window};
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+
+/// How often watched module files are checked for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The upgrade entrypoint of contracts following the `concordium-std` upgrade pattern.
+const DEFAULT_UPGRADE_ENTRYPOINT: &str = "upgrade";
+
+/// The watch tasks of a chain, keyed by the watched module path.
+pub(crate) type ContractWatchers = Arc<Mutex<HashMap<PathBuf, JoinHandle<()>>>>;
+
+/// A `.wasm.v1` file that is redeployed to the chain whenever it changes, e.g. after `cargo concordium build`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ContractWatch {
+    pub wasm_path: PathBuf,
+    /// Account that deploys the module and sends the follow-up transaction.
+    pub sender: AccountAddress,
+    #[serde(default)]
+    pub on_change: RedeployAction,
+}
+
+/// What to do after a changed module was deployed.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub(crate) enum RedeployAction {
+    #[default]
+    DeployOnly,
+    /// Initialize a new instance of the contract with the saved parameter.
+    Init {
+        #[serde(rename = "contractName")]
+        contract_name: String,
+        #[serde(default)]
+        param: JsonValue,
+        #[serde(default)]
+        amount: Option<Amount>,
+        /// Energy limit of the transaction, see [`crate::contracts::init`].
+        #[serde(default)]
+        energy: Option<u64>,
+    },
+    /// Upgrade an existing instance to the new module by calling its upgrade entrypoint.
+    Upgrade {
+        address: ContractAddress,
+        #[serde(default)]
+        entrypoint: Option<String>,
+    },
+}
+
+/// Payload of the `contract-redeployed` event.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RedeployEvent {
+    pub chain: String,
+    pub wasm_path: PathBuf,
+    pub deployment: Option<DeployOutcome>,
+    /// The instance that was initialized or upgraded, if the watch does either.
+    pub instance: Option<ContractOutcome>,
+    pub error: Option<String>,
+}
+
+/// Starts the watches saved in a chain's settings, so they survive restarting the chain.
+pub(crate) fn resume_watches(chain: &LocalChain, window: &Window) -> anyhow::Result<()> {
+    for watch in ChainSettings::load(&chain.folder)?.contract_watches {
+        start_watch(chain, window, watch);
+    }
+    Ok(())
+}
+
+// Spawns the task for a watch, replacing any earlier task watching the same file.
+fn start_watch(chain: &LocalChain, window: &Window, watch: ContractWatch) {
+    let path = watch.wasm_path.clone();
+    let task = tokio::spawn(run_watch(chain.clone(), window.clone(), watch));
+    if let Some(previous) = chain.watchers.lock().unwrap().insert(path, task) {
+        previous.abort();
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+async fn run_watch(chain: LocalChain, window: Window, watch: ContractWatch) {
+    println!("Watching {} for changes.", watch.wasm_path.display());
+
+    // Only changes made after the watch started are deployed.
+    let mut last_modified = modified(&watch.wasm_path);
+    while !chain.client.is_closed() {
+        tokio::time::sleep(WATCH_INTERVAL).await;
+
+        let current = modified(&watch.wasm_path);
+        if current.is_none() || current == last_modified {
+            continue;
+        }
+        // The build may still be writing the file, in which case it is read again on the next check.
+        let module = match read_module(&watch.wasm_path) {
+            Ok(module) => module,
+            Err(_) => continue,
+        };
+        last_modified = current;
+
+        let event = redeploy(&chain, &watch, module).await;
+        if let Some(error) = &event.error {
+            eprintln!(
+                "Failed to redeploy {}: {}",
+                watch.wasm_path.display(),
+                error
+            );
+        }
+        if let Err(e) = window.emit("contract-redeployed", event) {
+            eprintln!("Failed to emit contract-redeployed event: {}", e);
+        }
+    }
+}
+
+async fn redeploy(chain: &LocalChain, watch: &ContractWatch, module: WasmModule) -> RedeployEvent {
+    let mut event = RedeployEvent {
+        chain: chain_name(&chain.folder).unwrap_or_default(),
+        wasm_path: watch.wasm_path.clone(),
+        deployment: None,
+        instance: None,
+        error: None,
+    };
+    let sender = match chain.keys.signer(&watch.sender) {
+        Ok(sender) => sender,
+        Err(e) => {
+            event.error = Some(e.to_string());
+            return event;
+        }
+    };
+
+    let module_ref = module.get_module_ref();
+    let deployment = match deploy(&chain.client, &sender, module).await {
+        Ok(deployment) => deployment,
+        Err(e) => {
+            event.error = Some(e.to_string());
+            return event;
+        }
+    };
+    // A rebuild that produced the same module changes nothing on chain.
+    let already_deployed = deployment.already_deployed;
+    event.deployment = Some(deployment);
+    if already_deployed {
+        return event;
+    }
+
+    let instance = match &watch.on_change {
+        RedeployAction::DeployOnly => return event,
+        RedeployAction::Init {
+            contract_name,
+            param,
+            amount,
+            energy,
+        } => {
+            init(
+                &chain.client,
+                &chain.schemas,
+                &sender,
+                module_ref,
+                contract_name,
+                param,
+                amount.unwrap_or_else(Amount::zero),
+                *energy,
+            )
+            .await
+        }
+        RedeployAction::Upgrade {
+            address,
+            entrypoint,
+        } => match upgrade_parameter(module_ref) {
+            Ok(param) => {
+                update_with_parameter(
+                    &chain.client,
+                    &chain.schemas,
+                    &sender,
+                    *address,
+                    entrypoint.as_deref().unwrap_or(DEFAULT_UPGRADE_ENTRYPOINT),
+                    param,
+                    Amount::zero(),
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        },
+    };
+    match instance {
+        Ok(instance) => event.instance = Some(instance),
+        Err(e) => event.error = Some(e.to_string()),
+    }
+    event
+}
+
+// The `UpgradeParams` of `concordium-std` contracts: the new module followed by `None` for the optional migration call.
+fn upgrade_parameter(module_ref: ModuleReference) -> anyhow::Result<OwnedParameter> {
+    let module: &[u8] = module_ref.as_ref();
+    let mut bytes = module.to_vec();
+    bytes.push(0);
+    OwnedParameter::try_from(bytes).map_err(|_| anyhow::anyhow!("The parameter is too large."))
+}
+
+// Starts redeploying a `.wasm.v1` file to a chain whenever it changes. `on_change` optionally initializes a new
+// instance or upgrades an existing one after each deployment. Watches are saved in the chain settings.
+#[tauri::command]
+pub(crate) async fn watch_contract(
+    window: Window,
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    wasm_path: String,
+    sender: String,
+    on_change: Option<RedeployAction>,
+) -> Result<(), String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let sender = chain.aliases.resolve(&sender).map_err(|e| e.to_string())?;
+    chain.keys.signer(&sender).map_err(|e| e.to_string())?;
+    let wasm_path = PathBuf::from(wasm_path);
+    read_module(&wasm_path).map_err(|e| e.to_string())?;
+
+    let watch = ContractWatch {
+        wasm_path,
+        sender,
+        on_change: on_change.unwrap_or_default(),
+    };
+    ChainSettings::update(&chain.folder, |settings| {
+        settings
+            .contract_watches
+            .retain(|w| w.wasm_path != watch.wasm_path);
+        settings.contract_watches.push(watch.clone());
+    })
+    .map_err(|e| e.to_string())?;
+
+    start_watch(&chain, &window, watch);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn unwatch_contract(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+    wasm_path: String,
+) -> Result<(), String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let wasm_path = PathBuf::from(wasm_path);
+
+    ChainSettings::update(&chain.folder, |settings| {
+        settings
+            .contract_watches
+            .retain(|w| w.wasm_path != wasm_path);
+    })
+    .map_err(|e| e.to_string())?;
+
+    if let Some(task) = chain.watchers.lock().unwrap().remove(&wasm_path) {
+        task.abort();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn list_contract_watches(
+    app_state: State<'_, Arc<Mutex<AppState>>>,
+    chain: String,
+) -> Result<Vec<ContractWatch>, String> {
+    let chain = running_chain(&app_state, &chain)?;
+    let settings = ChainSettings::load(&chain.folder).map_err(|e| e.to_string())?;
+    Ok(settings.contract_watches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upgrade_parameter_is_the_module_reference_without_migration() {
+        let module_ref = ModuleReference::from([7u8; 32]);
+        let param = upgrade_parameter(module_ref).unwrap();
+        let bytes: &[u8] = param.as_ref();
+
+        assert_eq!(bytes.len(), 33);
+        assert_eq!(bytes[..32], [7u8; 32]);
+        assert_eq!(bytes[32], 0);
+    }
+}
@@ -698,6 +698,7 @@ function Dashboard() {
   const [amountDictFilter, setAmountsFilter] = useState<any>({});
   const [filterValue, setFilter] = useState("");
   const [amountDict, setTempDict] = useState<any>({});
+  const [aliasDict, setAliasDict] = useState<any>({});
   const [activeTab, setActiveTab] = useState("accounts");
 
   const addOrUpdateTransaction = (newTransactions: any) => {
@@ -724,6 +725,8 @@ function Dashboard() {
   useEffect(() => {
     let unlistenFn: UnlistenFn | undefined;
     let transactionsunlistenFn: UnlistenFn | undefined;
+    let balancesunlistenFn: UnlistenFn | undefined;
+    let balancesChangedunlistenFn: UnlistenFn | undefined;
 
     listen("new-block", (event: any) => {
       setBlockHeight(event.payload.number);
@@ -732,10 +735,6 @@ function Dashboard() {
       console.log("Received contracts:", event.payload.contracts);
 
       console.log("payload", event.payload);
-
-      if (filterValue.length == 0) {
-        setTempDict(event.payload.amounts);
-      }
     })
       .then((unlisten) => {
         unlistenFn = unlisten;
@@ -744,6 +743,36 @@ function Dashboard() {
         console.error("Error setting up listener:", error);
       });
 
+    // Full set of balances, sent when the indexer (re)connects to the node.
+    listen("balances", (event: any) => {
+      setTempDict(event.payload.amounts);
+      setAliasDict(event.payload.aliases ?? {});
+    })
+      .then((unlisten) => {
+        balancesunlistenFn = unlisten;
+      })
+      .catch((error) => {
+        console.error("Error setting up listener:", error);
+      });
+
+    // Only the balances that changed in a new block.
+    listen("balances-changed", (event: any) => {
+      setTempDict((prevAmounts: any) => ({
+        ...prevAmounts,
+        ...event.payload.amounts,
+      }));
+      setAliasDict((prevAliases: any) => ({
+        ...prevAliases,
+        ...event.payload.aliases,
+      }));
+    })
+      .then((unlisten) => {
+        balancesChangedunlistenFn = unlisten;
+      })
+      .catch((error) => {
+        console.error("Error setting up listener:", error);
+      });
+
     listen("transactions", (event: any) => {
       addOrUpdateTransaction(event.payload.transactions);
       console.log("payload transaction", event.payload);
@@ -764,6 +793,14 @@ function Dashboard() {
       if (transactionsunlistenFn) {
         transactionsunlistenFn();
       }
+
+      if (balancesunlistenFn) {
+        balancesunlistenFn();
+      }
+
+      if (balancesChangedunlistenFn) {
+        balancesChangedunlistenFn();
+      }
     };
   }, []);
 
@@ -1190,6 +1227,11 @@ function Dashboard() {
                       >
                         <td className="flex py-2 px-4 border-bottom-1 border-start-1 font-monospace border-opacity-25 border-black text-primary-dark relative">
                           <span className="mx-3">{x}</span>
+                          {aliasDict[x] && (
+                            <span className="me-3 text-black">
+                              {aliasDict[x]}
+                            </span>
+                          )}
                           {activeRow === x && (
                             <span
                               className=" cursor-pointer text-black  "
@@ -1239,6 +1281,11 @@ function Dashboard() {
                       >
                         <td className="flex py-2 px-4 border-bottom-1 border-start-1 font-monospace border-opacity-25 border-black text-primary-dark relative">
                           <span className="mx-3">{x}</span>
+                          {aliasDict[x] && (
+                            <span className="me-3 text-black">
+                              {aliasDict[x]}
+                            </span>
+                          )}
                           {activeRow === x && (
                             <span
                               className=" cursor-pointer text-black  "